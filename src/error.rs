@@ -0,0 +1,116 @@
+use std::fmt;
+
+/// Structural error found when a statement is validated, see [`Statement::validate`](crate::Statement::validate).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A row of an insert has a different number of values than the inserted columns,
+    /// or than the first row when no columns are given. Rows are numbered from 1.
+    ValueCount { table: String, row: usize, expected: usize, found: usize },
+    /// A row of an insert without any values. Rows are numbered from 1.
+    NoValues { table: String, row: usize },
+    /// An insert of `DEFAULT VALUES` with a column list.
+    DefaultValuesColumns { table: String },
+    /// An arm of a compound select has a different number of result columns than the first one.
+    /// Arms are numbered from 1, the first select being arm 0.
+    ResultColumnCount { arm: usize, expected: usize, found: usize },
+    /// An `ON CONFLICT` clause without a conflict target which is not the last one of an insert.
+    UpsertTarget { table: String },
+    /// A `REPLACE` with a conflict resolution, eg. `replace(..).or_ignore()`.
+    ReplaceResolution { table: String },
+    /// An arm of a compound select with its own `ORDER BY`, `LIMIT`, `OFFSET` or, except for the first one,
+    /// `WITH` clause. Arms are numbered from 1, the first select being arm 0.
+    CompoundArmClause { arm: usize, clause: String },
+    /// A table without any columns.
+    NoColumns { table: String },
+    /// A column defined twice in one table.
+    DuplicateColumn { table: String, column: String },
+    /// An update without any assignments.
+    NoAssignments { table: String },
+    /// An index without any indexed columns.
+    NoIndexedColumns { index: String },
+    /// An index without the indexed table, see [`Index::on`](crate::schema::Index::on).
+    MissingIndexTable { index: String },
+    /// A trigger without any statements.
+    NoTriggerStatements { trigger: String },
+    /// A foreign key without the referenced table.
+    MissingReference { column: String },
+    /// A foreign key references a different number of columns than it has.
+    ReferenceColumnCount { column: String, expected: usize, found: usize },
+    /// A generated column which is also the primary key.
+    GeneratedPrimaryKey { column: String },
+    /// An `AUTOINCREMENT` column which is not an `INTEGER PRIMARY KEY`.
+    Autoincrement { column: String },
+    /// A `WITHOUT ROWID` table without a primary key.
+    MissingPrimaryKey { table: String },
+    /// An `AUTOINCREMENT` column in a `WITHOUT ROWID` table.
+    AutoincrementWithoutRowid { table: String },
+    /// A column of a `STRICT` table with a type other than `INT`, `INTEGER`, `REAL`, `TEXT`, `BLOB` or `ANY`.
+    StrictType { table: String, column: String, typ: String },
+    /// An `ON CONFLICT` clause on a column constraint other than `PRIMARY KEY`, `NOT NULL` or `UNIQUE`.
+    ConflictClause { column: String },
+    /// A table with more than one primary key, eg. a column one and a table constraint.
+    MultiplePrimaryKeys { table: String },
+    /// A table constraint without any columns.
+    NoConstraintColumns { table: String },
+    /// A constraint refers to a column the table does not have.
+    UnknownColumn { table: String, column: String },
+    /// Two tables, views or indexes, or two triggers, with the same name.
+    DuplicateObject { name: String },
+    /// A foreign key, index, view or trigger, named by `object`, refers to a table or view which is not defined.
+    UnknownTable { object: String, table: String },
+    /// A foreign key of `table` references columns of `referenced` which are neither its primary key nor a unique key.
+    ForeignKeyMismatch { table: String, referenced: String },
+    /// A `BEFORE` or `AFTER` trigger on a view or an `INSTEAD OF` trigger on a table.
+    TriggerTarget { trigger: String, target: String },
+    /// An `ALTER TABLE` operation which rebuilds the table without the current definition of the table,
+    /// see [`Table::alter`](crate::schema::Table::alter).
+    MissingDefinition { table: String },
+    /// Anonymous `?` placeholders, bound values included, mixed with numbered or named ones, or bound
+    /// values mixed with unbound anonymous placeholders, so values would be bound to the wrong placeholders.
+    MixedPlaceholders
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ValueCount { table, row, expected, found } =>
+                write!(f, "row {} inserted into {} has {} values, expected {}", row, table, found, expected),
+            Self::NoValues { table, row } => write!(f, "row {} inserted into {} has no values", row, table),
+            Self::DefaultValuesColumns { table } => write!(f, "insert of DEFAULT VALUES into {} cannot list columns", table),
+            Self::ResultColumnCount { arm, expected, found } =>
+                write!(f, "compound select arm {} has {} result columns, expected {}", arm, found, expected),
+            Self::UpsertTarget { table } => write!(f, "only the last ON CONFLICT clause of insert into {} can omit the conflict target", table),
+            Self::ReplaceResolution { table } => write!(f, "REPLACE into {} cannot have another conflict resolution", table),
+            Self::CompoundArmClause { arm, clause } => write!(f, "compound select arm {} cannot have its own {} clause", arm, clause),
+            Self::NoColumns { table } => write!(f, "table {} has no columns", table),
+            Self::DuplicateColumn { table, column } => write!(f, "column {} is defined more than once in table {}", column, table),
+            Self::NoAssignments { table } => write!(f, "update of {} has no assignments", table),
+            Self::NoIndexedColumns { index } => write!(f, "index {} has no indexed columns", index),
+            Self::MissingIndexTable { index } => write!(f, "index {} is not on any table", index),
+            Self::NoTriggerStatements { trigger } => write!(f, "trigger {} has no statements", trigger),
+            Self::MissingReference { column } => write!(f, "foreign key on {} does not reference any table", column),
+            Self::ReferenceColumnCount { column, expected, found } =>
+                write!(f, "foreign key on {} references {} columns, expected {}", column, found, expected),
+            Self::GeneratedPrimaryKey { column } => write!(f, "generated column {} cannot be a primary key", column),
+            Self::Autoincrement { column } => write!(f, "AUTOINCREMENT column {} must be an INTEGER PRIMARY KEY", column),
+            Self::MissingPrimaryKey { table } => write!(f, "WITHOUT ROWID table {} has no primary key", table),
+            Self::AutoincrementWithoutRowid { table } => write!(f, "WITHOUT ROWID table {} cannot have an AUTOINCREMENT column", table),
+            Self::StrictType { table, column, typ } => write!(f, "column {} of STRICT table {} has type {}, which is not allowed", column, table, typ),
+            Self::ConflictClause { column } => write!(f, "ON CONFLICT on column {} is only allowed for PRIMARY KEY, NOT NULL and UNIQUE", column),
+            Self::MultiplePrimaryKeys { table } => write!(f, "table {} has more than one primary key", table),
+            Self::NoConstraintColumns { table } => write!(f, "constraint of table {} has no columns", table),
+            Self::UnknownColumn { table, column } => write!(f, "table {} has no column {}", table, column),
+            Self::DuplicateObject { name } => write!(f, "{} is defined more than once", name),
+            Self::UnknownTable { object, table } => write!(f, "{} refers to {}, which is not defined", object, table),
+            Self::ForeignKeyMismatch { table, referenced } =>
+                write!(f, "foreign key of {} must reference the primary key or a unique key of {}", table, referenced),
+            Self::TriggerTarget { trigger, target } =>
+                write!(f, "trigger {} cannot be created on {}, INSTEAD OF triggers are only allowed on views", trigger, target),
+            Self::MissingDefinition { table } => write!(f, "altering table {} needs its current definition", table),
+            Self::MixedPlaceholders => write!(f, "statement mixes bound values or ? placeholders with other placeholders")
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,695 @@
+//! Typed SQL expressions.
+//!
+//! Every builder method that takes a condition accepts anything convertible into [`Expr`].
+//! Plain strings are kept as raw SQL, so `where_("a = 1")` keeps working, and are parenthesised when
+//! combined with other expressions, unless they are a single name, number, placeholder or string.
+//! Expressions built from [`col`], [`lit`], [`func`] and the operator methods are parenthesised
+//! according to SQLite operator precedence.
+//!
+//! Note that a `&str` passed as an operand is raw SQL too, text literals have to be wrapped with [`lit`]:
+//! `col("name").eq(lit("Kowalski"))`, or bound with [`value`].
+
+use std::fmt;
+use std::ops;
+
+use crate::query::{Subquery, Window};
+use crate::{Value, Placeholder, Ident};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Is,
+    IsNot,
+    Like,
+    NotLike,
+    Glob,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Concat
+}
+
+impl BinaryOp {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Or => "OR",
+            Self::And => "AND",
+            Self::Eq => "=",
+            Self::Ne => "<>",
+            Self::Is => "IS",
+            Self::IsNot => "IS NOT",
+            Self::Like => "LIKE",
+            Self::NotLike => "NOT LIKE",
+            Self::Glob => "GLOB",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Rem => "%",
+            Self::Concat => "||"
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Or => prec::OR,
+            Self::And => prec::AND,
+            Self::Eq | Self::Ne | Self::Is | Self::IsNot | Self::Like | Self::NotLike | Self::Glob => prec::EQUALITY,
+            Self::Lt | Self::Le | Self::Gt | Self::Ge => prec::COMPARISON,
+            Self::Add | Self::Sub => prec::ADDITIVE,
+            Self::Mul | Self::Div | Self::Rem => prec::MULTIPLICATIVE,
+            Self::Concat => prec::CONCAT
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum UnaryOp {
+    Not,
+    Neg
+}
+
+/// Operator precedence levels, from the loosest to the tightest binding, see <https://www.sqlite.org/lang_expr.html#operators_and_parse_affecting_attributes>
+mod prec {
+    /// Raw SQL that may contain any operator.
+    pub const RAW: u8 = 0;
+    pub const OR: u8 = 1;
+    pub const AND: u8 = 2;
+    pub const NOT: u8 = 3;
+    pub const EQUALITY: u8 = 4;
+    pub const COMPARISON: u8 = 5;
+    pub const ADDITIVE: u8 = 6;
+    pub const MULTIPLICATIVE: u8 = 7;
+    pub const CONCAT: u8 = 8;
+    pub const UNARY: u8 = 9;
+    pub const ATOM: u8 = 10;
+}
+
+#[derive(Debug, Clone)]
+enum ExprKind {
+    Raw(String),
+    Column(Option<Ident>, Ident),
+    Literal(Value),
+    Param(Value),
+    Placeholder(Placeholder),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    Function(String, Vec<Expr>),
+    In(Box<Expr>, Vec<Expr>, bool),
+    InQuery(Box<Expr>, Box<Subquery>, bool),
+    Between(Box<Expr>, Box<Expr>, Box<Expr>, bool),
+    IsNull(Box<Expr>, bool),
+    Exists(Box<Subquery>),
+    Subquery(Box<Subquery>),
+    /// Aggregate or window function call with `FILTER` and `OVER` clauses.
+    Windowed(Box<Expr>, Option<Box<Expr>>, Option<Box<Window>>)
+}
+
+#[derive(Debug, Clone)]
+pub struct Expr(ExprKind);
+
+impl Expr {
+    /// Appends bound values in the order they are rendered.
+    pub(crate) fn collect_params(&self, params: &mut Vec<Value>) {
+        match &self.0 {
+            ExprKind::Param(v) => params.push(v.clone()),
+            ExprKind::Raw(_) | ExprKind::Column(..) | ExprKind::Literal(_) | ExprKind::Placeholder(_) => {},
+            ExprKind::Unary(_, e) | ExprKind::IsNull(e, _) => e.collect_params(params),
+            ExprKind::Binary(lhs, _, rhs) => {
+                lhs.collect_params(params);
+                rhs.collect_params(params);
+            },
+            ExprKind::Function(_, args) => args.iter().for_each(|a| a.collect_params(params)),
+            ExprKind::In(e, list, _) => {
+                e.collect_params(params);
+                list.iter().for_each(|a| a.collect_params(params));
+            },
+            ExprKind::InQuery(e, query, _) => {
+                e.collect_params(params);
+                query.collect_params(params);
+            },
+            ExprKind::Between(e, low, high, _) => {
+                e.collect_params(params);
+                low.collect_params(params);
+                high.collect_params(params);
+            },
+            ExprKind::Exists(query) | ExprKind::Subquery(query) => query.collect_params(params),
+            ExprKind::Windowed(call, filter, over) => {
+                call.collect_params(params);
+                if let Some(filter) = filter {
+                    filter.collect_params(params);
+                }
+                if let Some(over) = over {
+                    over.collect_params(params);
+                }
+            }
+        }
+    }
+
+    /// Whether the expression can be followed by a postfix clause, eg. `COLLATE`, without parentheses.
+    pub(crate) fn is_atomic(&self) -> bool {
+        self.precedence() == prec::ATOM
+    }
+
+    fn binary(self, op: BinaryOp, rhs: impl Into<Expr>) -> Self {
+        Self(ExprKind::Binary(Box::new(self), op, Box::new(rhs.into())))
+    }
+
+    fn precedence(&self) -> u8 {
+        match self.0 {
+            ExprKind::Unary(UnaryOp::Not, _) => prec::NOT,
+            ExprKind::Unary(UnaryOp::Neg, _) => prec::UNARY,
+            ExprKind::Binary(_, op, _) => op.precedence(),
+            ExprKind::In(..) | ExprKind::InQuery(..) | ExprKind::Between(..) | ExprKind::IsNull(..) => prec::EQUALITY,
+            ExprKind::Raw(ref sql) if !is_single_token(sql) => prec::RAW,
+            _ => prec::ATOM
+        }
+    }
+
+    pub fn and(self, rhs: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::And, rhs)
+    }
+
+    pub fn or(self, rhs: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Or, rhs)
+    }
+
+    pub fn eq(self, rhs: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Eq, rhs)
+    }
+
+    pub fn ne(self, rhs: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Ne, rhs)
+    }
+
+    pub fn lt(self, rhs: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Lt, rhs)
+    }
+
+    pub fn le(self, rhs: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Le, rhs)
+    }
+
+    pub fn gt(self, rhs: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Gt, rhs)
+    }
+
+    pub fn ge(self, rhs: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Ge, rhs)
+    }
+
+    pub fn is(self, rhs: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Is, rhs)
+    }
+
+    pub fn is_not(self, rhs: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::IsNot, rhs)
+    }
+
+    pub fn like(self, pattern: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Like, pattern)
+    }
+
+    pub fn not_like(self, pattern: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::NotLike, pattern)
+    }
+
+    pub fn glob(self, pattern: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Glob, pattern)
+    }
+
+    pub fn concat(self, rhs: impl Into<Expr>) -> Self {
+        self.binary(BinaryOp::Concat, rhs)
+    }
+
+    pub fn is_null(self) -> Self {
+        Self(ExprKind::IsNull(Box::new(self), false))
+    }
+
+    pub fn is_not_null(self) -> Self {
+        Self(ExprKind::IsNull(Box::new(self), true))
+    }
+
+    pub fn in_list<I>(self, list: I) -> Self where I: IntoIterator, I::Item: Into<Expr> {
+        Self(ExprKind::In(Box::new(self), list.into_iter().map(Into::into).collect(), false))
+    }
+
+    pub fn not_in_list<I>(self, list: I) -> Self where I: IntoIterator, I::Item: Into<Expr> {
+        Self(ExprKind::In(Box::new(self), list.into_iter().map(Into::into).collect(), true))
+    }
+
+    pub fn in_query(self, query: impl Into<Subquery>) -> Self {
+        Self(ExprKind::InQuery(Box::new(self), Box::new(query.into()), false))
+    }
+
+    pub fn not_in_query(self, query: impl Into<Subquery>) -> Self {
+        Self(ExprKind::InQuery(Box::new(self), Box::new(query.into()), true))
+    }
+
+    pub fn between(self, low: impl Into<Expr>, high: impl Into<Expr>) -> Self {
+        Self(ExprKind::Between(Box::new(self), Box::new(low.into()), Box::new(high.into()), false))
+    }
+
+    /// Adds `FILTER (WHERE cond)` to an aggregate or window function call.
+    pub fn filter(self, cond: impl Into<Expr>) -> Self {
+        match self.0 {
+            ExprKind::Windowed(call, _, over) => Self(ExprKind::Windowed(call, Some(Box::new(cond.into())), over)),
+            _ => Self(ExprKind::Windowed(Box::new(self), Some(Box::new(cond.into())), None))
+        }
+    }
+
+    /// Turns a function call into a window function call, a `&str` is a name of a window from the `WINDOW` clause.
+    pub fn over(self, window: impl Into<Window>) -> Self {
+        match self.0 {
+            ExprKind::Windowed(call, filter, _) => Self(ExprKind::Windowed(call, filter, Some(Box::new(window.into())))),
+            _ => Self(ExprKind::Windowed(Box::new(self), None, Some(Box::new(window.into()))))
+        }
+    }
+
+    pub fn not_between(self, low: impl Into<Expr>, high: impl Into<Expr>) -> Self {
+        Self(ExprKind::Between(Box::new(self), Box::new(low.into()), Box::new(high.into()), true))
+    }
+}
+
+/// Whether raw SQL is a single name, number, placeholder or string, or is wrapped in parentheses,
+/// so that it needs no parentheses as an operand.
+fn is_single_token(sql: &str) -> bool {
+    let name = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || "_.$?:@".contains(c));
+    let quoted = |s: &str, q: char| s.len() >= 2 && s.starts_with(q) && s.ends_with(q) && !s[1..s.len() - 1].replace(&q.to_string().repeat(2), "").contains(q);
+    let sql = sql.trim();
+    sql == "*" || name(sql) || sql.strip_suffix(".*").is_some_and(name) || quoted(sql, '\'') || quoted(sql, '"') || is_parenthesized(sql)
+}
+
+/// Whether the first `(` of the SQL is closed by its last character.
+fn is_parenthesized(sql: &str) -> bool {
+    if !sql.starts_with('(') {
+        return false;
+    }
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, c) in sql.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {},
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return i == sql.len() - 1;
+                }
+            },
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Writes `expr`, wrapping it in parentheses when it binds looser than `min_prec`.
+fn fmt_operand(f: &mut fmt::Formatter, expr: &Expr, min_prec: u8) -> fmt::Result {
+    if expr.precedence() < min_prec {
+        write!(f, "({})", expr)
+    } else {
+        write!(f, "{}", expr)
+    }
+}
+
+fn fmt_list(f: &mut fmt::Formatter, list: &[Expr]) -> fmt::Result {
+    for (i, it) in list.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", it)?;
+    }
+    Ok(())
+}
+
+fn not_str(negated: bool) -> &'static str {
+    if negated { "NOT " } else { "" }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            ExprKind::Raw(sql) => write!(f, "{}", sql),
+            ExprKind::Column(table, name) => {
+                if let Some(t) = table {
+                    write!(f, "{}.", t)?;
+                }
+                if name.as_str() == "*" {
+                    write!(f, "*")
+                } else {
+                    write!(f, "{}", name)
+                }
+            },
+            ExprKind::Literal(lit) => write!(f, "{}", lit),
+            ExprKind::Param(_) => write!(f, "?"),
+            ExprKind::Placeholder(p) => write!(f, "{}", p),
+            ExprKind::Unary(UnaryOp::Not, e) => {
+                write!(f, "NOT ")?;
+                fmt_operand(f, e, prec::NOT)
+            },
+            ExprKind::Unary(UnaryOp::Neg, e) => {
+                let operand = if e.precedence() < prec::ATOM { format!("({})", e) } else { e.to_string() };
+                // `--` would start a comment.
+                let sep = if operand.starts_with('-') { " " } else { "" };
+                write!(f, "-{}{}", sep, operand)
+            },
+            ExprKind::Binary(lhs, op, rhs) => {
+                let p = op.precedence();
+                fmt_operand(f, lhs, p)?;
+                write!(f, " {} ", op.as_str())?;
+                fmt_operand(f, rhs, p + 1)
+            },
+            ExprKind::Function(name, args) => {
+                write!(f, "{}(", name)?;
+                fmt_list(f, args)?;
+                write!(f, ")")
+            },
+            ExprKind::In(e, list, negated) => {
+                fmt_operand(f, e, prec::COMPARISON)?;
+                write!(f, " {}IN (", not_str(*negated))?;
+                fmt_list(f, list)?;
+                write!(f, ")")
+            },
+            ExprKind::InQuery(e, query, negated) => {
+                fmt_operand(f, e, prec::COMPARISON)?;
+                write!(f, " {}IN ({})", not_str(*negated), query)
+            },
+            ExprKind::Between(e, low, high, negated) => {
+                fmt_operand(f, e, prec::COMPARISON)?;
+                write!(f, " {}BETWEEN ", not_str(*negated))?;
+                fmt_operand(f, low, prec::COMPARISON)?;
+                write!(f, " AND ")?;
+                fmt_operand(f, high, prec::COMPARISON)
+            },
+            ExprKind::IsNull(e, negated) => {
+                fmt_operand(f, e, prec::COMPARISON)?;
+                write!(f, " IS {}NULL", not_str(*negated))
+            },
+            ExprKind::Exists(query) => write!(f, "EXISTS ({})", query),
+            ExprKind::Subquery(query) => write!(f, "({})", query),
+            ExprKind::Windowed(call, filter, over) => {
+                write!(f, "{}", call)?;
+                if let Some(filter) = filter {
+                    write!(f, " FILTER (WHERE {})", filter)?;
+                }
+                if let Some(over) = over {
+                    write!(f, " OVER {}", over)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<&str> for Expr {
+    fn from(sql: &str) -> Self {
+        raw(sql)
+    }
+}
+
+impl From<&String> for Expr {
+    fn from(sql: &String) -> Self {
+        raw(sql)
+    }
+}
+
+impl From<String> for Expr {
+    fn from(sql: String) -> Self {
+        Self(ExprKind::Raw(sql))
+    }
+}
+
+/// Binds the value, it is rendered as `?` and returned by [`Statement::params`](crate::Statement::params).
+impl From<Value> for Expr {
+    fn from(v: Value) -> Self {
+        value(v)
+    }
+}
+
+impl From<Placeholder> for Expr {
+    fn from(p: Placeholder) -> Self {
+        Expr(ExprKind::Placeholder(p))
+    }
+}
+
+macro_rules! implement_expr_from {
+    ($t: ty) => {
+        impl From<$t> for Expr {
+            fn from(value: $t) -> Self {
+                lit(value)
+            }
+        }
+    }
+}
+
+implement_expr_from!(i64);
+implement_expr_from!(i32);
+implement_expr_from!(u32);
+implement_expr_from!(f64);
+implement_expr_from!(bool);
+
+macro_rules! implement_binary_operator {
+    ($trait: ident, $method: ident, $op: expr) => {
+        impl<T> ops::$trait<T> for Expr where T: Into<Expr> {
+            type Output = Expr;
+
+            fn $method(self, rhs: T) -> Expr {
+                self.binary($op, rhs)
+            }
+        }
+    }
+}
+
+implement_binary_operator!(Add, add, BinaryOp::Add);
+implement_binary_operator!(Sub, sub, BinaryOp::Sub);
+implement_binary_operator!(Mul, mul, BinaryOp::Mul);
+implement_binary_operator!(Div, div, BinaryOp::Div);
+implement_binary_operator!(Rem, rem, BinaryOp::Rem);
+
+impl ops::Not for Expr {
+    type Output = Expr;
+
+    fn not(self) -> Expr {
+        Self(ExprKind::Unary(UnaryOp::Not, Box::new(self)))
+    }
+}
+
+impl ops::Neg for Expr {
+    type Output = Expr;
+
+    fn neg(self) -> Expr {
+        Self(ExprKind::Unary(UnaryOp::Neg, Box::new(self)))
+    }
+}
+
+/// Column reference, `"table.column"` is split into a qualified reference. Both parts are quoted when needed.
+pub fn col(name: &str) -> Expr {
+    match name.split_once('.') {
+        Some((table, column)) => Expr(ExprKind::Column(Some(Ident::new(table)), Ident::new(column))),
+        None => Expr(ExprKind::Column(None, Ident::new(name)))
+    }
+}
+
+/// Column reference, unlike [`col`] the name is not split.
+pub(crate) fn unqualified_col(name: &str) -> Expr {
+    Expr(ExprKind::Column(None, Ident::new(name)))
+}
+
+/// Column reference qualified with its table, unlike [`col`] neither name is split.
+pub(crate) fn qualified_col(table: &str, column: &str) -> Expr {
+    Expr(ExprKind::Column(Some(Ident::new(table)), Ident::new(column)))
+}
+
+/// Value inlined into the SQL text as a literal.
+pub fn lit(value: impl Into<Value>) -> Expr {
+    Expr(ExprKind::Literal(value.into()))
+}
+
+pub fn null() -> Expr {
+    Expr(ExprKind::Literal(Value::Null))
+}
+
+/// Value bound to an anonymous `?` placeholder.
+pub fn value(value: impl Into<Value>) -> Expr {
+    Expr(ExprKind::Param(value.into()))
+}
+
+/// Unbound `?` placeholder.
+pub fn param() -> Expr {
+    Placeholder::Anonymous.into()
+}
+
+/// Unbound `?NNN` placeholder.
+pub fn param_at(index: u32) -> Expr {
+    Placeholder::Numbered(index).into()
+}
+
+/// Unbound `:name` placeholder.
+pub fn named_param(name: &str) -> Expr {
+    Placeholder::Named(name.to_owned()).into()
+}
+
+/// SQL fragment rendered verbatim, it is parenthesised when combined with other expressions unless it
+/// is a single name, number, placeholder or string or is already parenthesised.
+pub fn raw(sql: &str) -> Expr {
+    Expr(ExprKind::Raw(sql.to_owned()))
+}
+
+pub fn func<I>(name: &str, args: I) -> Expr where I: IntoIterator, I::Item: Into<Expr> {
+    Expr(ExprKind::Function(name.to_owned(), args.into_iter().map(Into::into).collect()))
+}
+
+pub fn not(expr: impl Into<Expr>) -> Expr {
+    !expr.into()
+}
+
+pub fn exists(query: impl Into<Subquery>) -> Expr {
+    Expr(ExprKind::Exists(Box::new(query.into())))
+}
+
+pub fn subquery(query: impl Into<Subquery>) -> Expr {
+    Expr(ExprKind::Subquery(Box::new(query.into())))
+}
+
+/// Joins `rhs` to an optional condition with AND.
+pub(crate) fn push_and(cond: &mut Option<Expr>, rhs: Expr) {
+    *cond = Some(match cond.take() {
+        Some(lhs) => lhs.and(rhs),
+        None => rhs
+    });
+}
+
+/// Joins `rhs` to an optional condition with OR.
+pub(crate) fn push_or(cond: &mut Option<Expr>, rhs: Expr) {
+    *cond = Some(match cond.take() {
+        Some(lhs) => lhs.or(rhs),
+        None => rhs
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::select;
+
+    #[test]
+    fn literals() {
+        assert_eq!(lit(1).to_string(), "1");
+        assert_eq!(lit("O'Hara").to_string(), "'O''Hara'");
+        assert_eq!(null().to_string(), "NULL");
+    }
+
+    #[test]
+    fn params() {
+        let e = col("a").eq(value(1)).and(col("b").in_list([value("x"), lit("y"), param()])).or(col("c").eq(named_param("c")));
+        assert_eq!(e.to_string(), "a = ? AND b IN (?, 'y', ?) OR c = :c");
+
+        let mut params = Vec::new();
+        e.collect_params(&mut params);
+        assert_eq!(params, vec![Value::Integer(1), Value::Text("x".into())]);
+
+        assert_eq!(col("a").eq(param_at(2)).to_string(), "a = ?2");
+    }
+
+    #[test]
+    fn columns() {
+        assert_eq!(col("age").to_string(), "age");
+        assert_eq!(col("p.age").to_string(), "p.age");
+        assert_eq!(col("order").to_string(), "\"order\"");
+        assert_eq!(col("o.group").to_string(), "o.\"group\"");
+        assert_eq!(col("p.*").to_string(), "p.*");
+    }
+
+    #[test]
+    fn precedence() {
+        let e = col("a").eq(1).or(col("b").eq(2)).and(col("c").eq(3));
+        assert_eq!(e.to_string(), "(a = 1 OR b = 2) AND c = 3");
+
+        let e = col("a").eq(1).and(col("b").eq(2)).or(col("c").eq(3));
+        assert_eq!(e.to_string(), "a = 1 AND b = 2 OR c = 3");
+
+        let e = (col("a") + col("b")) * 2;
+        assert_eq!(e.to_string(), "(a + b) * 2");
+
+        let e = col("a") - (col("b") - col("c"));
+        assert_eq!(e.to_string(), "a - (b - c)");
+
+        let e = col("a") * col("b") + col("c");
+        assert_eq!(e.to_string(), "a * b + c");
+
+        let e = !(col("a").eq(1).and(col("b").eq(2)));
+        assert_eq!(e.to_string(), "NOT (a = 1 AND b = 2)");
+
+        let e = -(-col("a"));
+        assert_eq!(e.to_string(), "-(-a)");
+    }
+
+    #[test]
+    fn raw_operands() {
+        let e = Expr::from("a = 1 OR b = 2").and("c = 3");
+        assert_eq!(e.to_string(), "(a = 1 OR b = 2) AND (c = 3)");
+        assert_eq!(not("a = 1 OR b = 2").to_string(), "NOT (a = 1 OR b = 2)");
+        assert_eq!(col("a").eq("b.id").and("'it''s'").to_string(), "a = b.id AND 'it''s'");
+        assert_eq!(col("a").eq("?1").or(":name").to_string(), "a = ?1 OR :name");
+        assert_eq!(Expr::from("count(*)").gt(1).to_string(), "(count(*)) > 1");
+        assert_eq!(Expr::from("(a OR b)").and("(c) OR (d)").to_string(), "(a OR b) AND ((c) OR (d))");
+    }
+
+    #[test]
+    fn negation() {
+        assert_eq!((-lit(-5)).to_string(), "- -5");
+        assert_eq!((-Expr::from("-x")).to_string(), "-(-x)");
+        assert_eq!((-col("a")).to_string(), "-a");
+    }
+
+    #[test]
+    fn predicates() {
+        assert_eq!(col("name").like(lit("K%")).to_string(), "name LIKE 'K%'");
+        assert_eq!(col("name").not_like(lit("K%")).to_string(), "name NOT LIKE 'K%'");
+        assert_eq!(col("name").glob(lit("K*")).to_string(), "name GLOB 'K*'");
+        assert_eq!(col("age").is_null().to_string(), "age IS NULL");
+        assert_eq!(col("age").is_not_null().to_string(), "age IS NOT NULL");
+        assert_eq!(col("age").between(18, 65).to_string(), "age BETWEEN 18 AND 65");
+        assert_eq!(col("age").not_between(col("a") + 1, 65).to_string(), "age NOT BETWEEN a + 1 AND 65");
+        assert_eq!(col("id").in_list([1, 2, 3]).to_string(), "id IN (1, 2, 3)");
+        assert_eq!(col("id").not_in_list([lit("a"), lit("b")]).to_string(), "id NOT IN ('a', 'b')");
+        assert_eq!(col("a").is(null()).to_string(), "a IS NULL");
+        assert_eq!(col("a").is_not(col("b")).to_string(), "a IS NOT b");
+        assert_eq!(col("a").concat(lit("x")).to_string(), "a || 'x'");
+        assert_eq!(col("a").eq(1).is_null().to_string(), "(a = 1) IS NULL");
+    }
+
+    #[test]
+    fn functions() {
+        let e = func("coalesce", [col("nick"), col("name"), lit("?")]);
+        assert_eq!(e.to_string(), "coalesce(nick, name, '?')");
+        assert_eq!(func("count", ["*"]).gt(1).to_string(), "count(*) > 1");
+    }
+
+    #[test]
+    fn subqueries() {
+        let e = col("userId").in_query(select(&["userId"]).from("users").where_("banned = TRUE"));
+        assert_eq!(e.to_string(), "userId IN (SELECT userId FROM users WHERE banned = TRUE)");
+
+        let e = not(exists(select(&["1"]).from("orders").where_(col("orders.userId").eq(col("users.id")))));
+        assert_eq!(e.to_string(), "NOT EXISTS (SELECT 1 FROM orders WHERE orders.userId = users.id)");
+
+        let e = col("salary").gt(subquery(select(&["avg(salary)"]).from("employees")));
+        assert_eq!(e.to_string(), "salary > (SELECT avg(salary) FROM employees)");
+    }
+}
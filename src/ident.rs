@@ -0,0 +1,191 @@
+use std::fmt;
+
+/// SQLite keywords, see <https://www.sqlite.org/lang_keywords.html>. Must stay sorted.
+const KEYWORDS: [&str; 147] = [
+    "ABORT", "ACTION", "ADD", "AFTER", "ALL", "ALTER", "ALWAYS", "ANALYZE", "AND", "AS", "ASC", "ATTACH",
+    "AUTOINCREMENT", "BEFORE", "BEGIN", "BETWEEN", "BY", "CASCADE", "CASE", "CAST", "CHECK", "COLLATE",
+    "COLUMN", "COMMIT", "CONFLICT", "CONSTRAINT", "CREATE", "CROSS", "CURRENT", "CURRENT_DATE",
+    "CURRENT_TIME", "CURRENT_TIMESTAMP", "DATABASE", "DEFAULT", "DEFERRABLE", "DEFERRED", "DELETE", "DESC",
+    "DETACH", "DISTINCT", "DO", "DROP", "EACH", "ELSE", "END", "ESCAPE", "EXCEPT", "EXCLUDE", "EXCLUSIVE",
+    "EXISTS", "EXPLAIN", "FAIL", "FILTER", "FIRST", "FOLLOWING", "FOR", "FOREIGN", "FROM", "FULL",
+    "GENERATED", "GLOB", "GROUP", "GROUPS", "HAVING", "IF", "IGNORE", "IMMEDIATE", "IN", "INDEX", "INDEXED",
+    "INITIALLY", "INNER", "INSERT", "INSTEAD", "INTERSECT", "INTO", "IS", "ISNULL", "JOIN", "KEY", "LAST",
+    "LEFT", "LIKE", "LIMIT", "MATCH", "MATERIALIZED", "NATURAL", "NO", "NOT", "NOTHING", "NOTNULL", "NULL",
+    "NULLS", "OF", "OFFSET", "ON", "OR", "ORDER", "OTHERS", "OUTER", "OVER", "PARTITION", "PLAN", "PRAGMA",
+    "PRECEDING", "PRIMARY", "QUERY", "RAISE", "RANGE", "RECURSIVE", "REFERENCES", "REGEXP", "REINDEX",
+    "RELEASE", "RENAME", "REPLACE", "RESTRICT", "RETURNING", "RIGHT", "ROLLBACK", "ROW", "ROWS", "SAVEPOINT",
+    "SELECT", "SET", "TABLE", "TEMP", "TEMPORARY", "THEN", "TIES", "TO", "TRANSACTION", "TRIGGER",
+    "UNBOUNDED", "UNION", "UNIQUE", "UPDATE", "USING", "VACUUM", "VALUES", "VIEW", "VIRTUAL", "WHEN", "WHERE",
+    "WINDOW", "WITH", "WITHOUT"
+];
+
+/// Name of a table, column, view, trigger or index.
+///
+/// It is rendered verbatim when it is a plain identifier and quoted with `"..."` when it is a keyword
+/// or contains characters other than ASCII letters, digits and underscores.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Ident(String);
+
+impl Ident {
+    pub fn new(name: &str) -> Self {
+        Self(name.to_owned())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn needs_quoting(&self) -> bool {
+        let mut chars = self.0.chars();
+        let plain = match chars.next() {
+            Some(c) => (c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+            None => false
+        };
+        !plain || is_keyword(&self.0)
+    }
+}
+
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.needs_quoting() {
+            write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+impl From<&str> for Ident {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+pub fn is_keyword(word: &str) -> bool {
+    KEYWORDS.binary_search(&word.to_ascii_uppercase().as_str()).is_ok()
+}
+
+/// Table name, `"schema.table"` is split into a schema qualified name. Both parts are quoted when needed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub(crate) struct TableName {
+    schema: Option<Ident>,
+    name: Ident
+}
+
+impl TableName {
+    pub fn new(name: &str) -> Self {
+        match name.split_once('.') {
+            Some((schema, table)) => Self { schema: Some(Ident::new(schema)), name: Ident::new(table) },
+            None => Self { schema: None, name: Ident::new(name) }
+        }
+    }
+
+    /// Name of the table, without the schema.
+    pub fn as_str(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl fmt::Display for TableName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref schema) = self.schema {
+            write!(f, "{}.", schema)?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Keywords which are expressions on their own and must not be quoted as column names.
+const VALUE_KEYWORDS: [&str; 4] = ["CURRENT_DATE", "CURRENT_TIME", "CURRENT_TIMESTAMP", "NULL"];
+
+/// Column given as SQL, a bare name such as `order` or `t.group` is quoted when needed, anything else
+/// such as `count(*)`, `t.*` or `1` is left as it is.
+pub(crate) fn column_sql(sql: &str) -> String {
+    let is_name = |part: &str| {
+        let mut chars = part.chars();
+        matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    let parts = sql.split('.').collect::<Vec<&str>>();
+    if parts.len() > 2 || !parts.iter().all(|it| is_name(it)) || VALUE_KEYWORDS.contains(&sql.to_ascii_uppercase().as_str()) {
+        return sql.to_owned();
+    }
+    parts.iter().map(|it| Ident::new(it).to_string()).collect::<Vec<String>>().join(".")
+}
+
+/// Comma separated list of identifiers.
+pub(crate) fn fmt_idents(idents: &[Ident]) -> String {
+    idents.iter().map(|it| it.to_string()).collect::<Vec<String>>().join(", ")
+}
+
+pub(crate) fn idents(names: &[&str]) -> Vec<Ident> {
+    names.iter().map(|it| Ident::new(it)).collect()
+}
+
+/// Text literal, with `'` escaped by doubling it.
+pub fn text_literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Blob literal in the `X'...'` hexadecimal form.
+pub fn blob_literal(blob: &[u8]) -> String {
+    let mut s = String::with_capacity(blob.len() * 2 + 3);
+    s.push_str("X'");
+    for b in blob {
+        s.push_str(&format!("{:02X}", b));
+    }
+    s.push('\'');
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords_sorted() {
+        assert!(KEYWORDS.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(Ident::new("users").to_string(), "users");
+        assert_eq!(Ident::new("_user_id2").to_string(), "_user_id2");
+    }
+
+    #[test]
+    fn quoted() {
+        assert_eq!(Ident::new("order").to_string(), "\"order\"");
+        assert_eq!(Ident::new("Group").to_string(), "\"Group\"");
+        assert_eq!(Ident::new("first name").to_string(), "\"first name\"");
+        assert_eq!(Ident::new("2fa").to_string(), "\"2fa\"");
+        assert_eq!(Ident::new("say \"hi\"").to_string(), "\"say \"\"hi\"\"\"");
+        assert_eq!(Ident::new("").to_string(), "\"\"");
+    }
+
+    #[test]
+    fn table_names() {
+        assert_eq!(TableName::new("users").to_string(), "users");
+        assert_eq!(TableName::new("main.users").to_string(), "main.users");
+        assert_eq!(TableName::new("aux.order").to_string(), "aux.\"order\"");
+        assert_eq!(TableName::new("main.users").as_str(), "users");
+    }
+
+    #[test]
+    fn column_names() {
+        assert_eq!(column_sql("name"), "name");
+        assert_eq!(column_sql("order"), "\"order\"");
+        assert_eq!(column_sql("g.group"), "g.\"group\"");
+        assert_eq!(column_sql("count(*)"), "count(*)");
+        assert_eq!(column_sql("t.*"), "t.*");
+        assert_eq!(column_sql("1"), "1");
+        assert_eq!(column_sql("null"), "null");
+        assert_eq!(column_sql("name AS n"), "name AS n");
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(text_literal("it's"), "'it''s'");
+        assert_eq!(blob_literal(&[0, 0x7F, 0xFF]), "X'007FFF'");
+        assert_eq!(blob_literal(&[]), "X''");
+    }
+}
@@ -0,0 +1,380 @@
+//! Schema of an existing database read back into yaqub types, with the `introspect` feature.
+//!
+//! [`read`] builds a [`Database`] from `sqlite_schema` and the `table_list`, `table_xinfo`,
+//! `foreign_key_list`, `index_list` and `index_xinfo` pragmas. What the pragmas do not report,
+//! `CHECK` constraints, collations, generated columns and `AUTOINCREMENT`, is read from the SQL stored
+//! in `sqlite_schema`, and views, triggers and indexes are read with [`parse`].
+//!
+//! The DDL of the result is equivalent to the original one rather than identical: keys and foreign
+//! keys become table constraints, and constraint names and conflict clauses are not kept. Virtual
+//! tables and their shadow tables are skipped.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::lex::Tokens;
+use crate::migrate::Executor;
+use crate::parse;
+use crate::query::select;
+use crate::schema::{create_table, Check, Column, Database, ForeignKey, ForeignKeyAction, ForeignKeyMatch, GeneratedColumnType, Table};
+use crate::{Ident, Value};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error<E> {
+    /// Error returned by the [`Executor`].
+    Database(E),
+    /// A view, trigger or index using SQL yaqub cannot represent, eg. an `UPDATE OF` trigger.
+    Unsupported { object: String, sql: String }
+}
+
+impl<E> fmt::Display for Error<E> where E: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Database(e) => write!(f, "database error: {}", e),
+            Self::Unsupported { object, sql } => write!(f, "{} cannot be represented: {}", object, sql)
+        }
+    }
+}
+
+impl<E> std::error::Error for Error<E> where E: fmt::Debug + fmt::Display {}
+
+/// Row of `sqlite_schema`.
+struct Object {
+    typ: String,
+    name: String,
+    sql: String
+}
+
+/// Reads the schema of the `main` database of the connection.
+pub fn read<E>(db: &mut E) -> Result<Database, Error<E::Error>> where E: Executor {
+    let query = select(&["type", "name", "sql"]).from("sqlite_schema").order_by("rowid").to_string();
+    let objects = db.query(&query).map_err(Error::Database)?.into_iter()
+        .filter_map(|row| match row.as_slice() {
+            [Value::Text(typ), Value::Text(name), Value::Text(sql)] => Some(Object { typ: typ.clone(), name: name.clone(), sql: sql.clone() }),
+            _ => None
+        })
+        .filter(|o| !o.name.to_ascii_lowercase().starts_with("sqlite_"))
+        .collect::<Vec<Object>>();
+    let mut database = Database::new();
+    for object in objects.iter().filter(|o| o.typ == "table") {
+        if let Some(table) = read_table(db, object, &objects)? {
+            database.add_table(table);
+        }
+    }
+    for object in objects.iter().filter(|o| o.typ == "view") {
+        if let parse::Object::View(view) = parse_object(object)? {
+            database.add_view(view);
+        }
+    }
+    for object in objects.iter().filter(|o| o.typ == "trigger") {
+        if let parse::Object::Trigger(trigger) = parse_object(object)? {
+            database.add_trigger(trigger);
+        }
+    }
+    Ok(database)
+}
+
+/// Reads the schema of a database file, which is opened read-only.
+pub fn read_file(path: impl AsRef<std::path::Path>) -> Result<Database, Error<rusqlite::Error>> {
+    let mut db = rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(Error::Database)?;
+    read(&mut db)
+}
+
+fn pragma<E>(db: &mut E, pragma: &str, name: &str) -> Result<Vec<Vec<Value>>, Error<E::Error>> where E: Executor {
+    db.query(&format!("PRAGMA {}({});", pragma, Ident::new(name))).map_err(Error::Database)
+}
+
+fn text(value: &Value) -> &str {
+    match value {
+        Value::Text(text) => text,
+        _ => ""
+    }
+}
+
+fn int(value: &Value) -> i64 {
+    match value {
+        Value::Integer(v) => *v,
+        _ => 0
+    }
+}
+
+/// The table, `None` for virtual and shadow tables.
+fn read_table<E>(db: &mut E, object: &Object, objects: &[Object]) -> Result<Option<Table>, Error<E::Error>> where E: Executor {
+    let list = pragma(db, "table_list", &object.name)?;
+    let (without_rowid, strict) = match list.iter().find(|r| r.len() >= 6 && text(&r[0]) == "main") {
+        Some(row) if text(&row[2]) == "table" => (int(&row[4]) != 0, int(&row[5]) != 0),
+        _ => return Ok(None)
+    };
+    let tokens = Tokens::new(&object.sql);
+    let definition = TableSql::new(&tokens);
+    let mut table = create_table(&object.name);
+
+    let columns = pragma(db, "table_xinfo", &object.name)?;
+    let mut primary_key = columns.iter()
+        .filter(|r| r.len() >= 7 && int(&r[5]) > 0)
+        .map(|r| (int(&r[5]), text(&r[1])))
+        .collect::<Vec<(i64, &str)>>();
+    primary_key.sort();
+    let rowid_alias = matches!(primary_key.as_slice(), [(_, name)] if columns.iter().any(|r| text(&r[1]) == *name && text(&r[2]).eq_ignore_ascii_case("INTEGER")));
+    for row in columns.iter().filter(|r| r.len() >= 7 && int(&r[6]) != 1) {
+        let name = text(&row[1]);
+        let range = definition.column(name).unwrap_or(0..0);
+        let mut col = Column::new(name).declared_type(text(&row[2]));
+        if int(&row[3]) == 0 {
+            col = col.nullable();
+        }
+        if rowid_alias && int(&row[5]) == 1 {
+            col = match definition.keyword(&range, "AUTOINCREMENT") {
+                Some(_) => col.autoincrement(),
+                None => col.primary_key()
+            };
+            if definition.keyword(&range, "DESC").is_some() {
+                col = col.desc();
+            }
+        }
+        if let Some(check) = definition.checks(range.clone()) {
+            col = col.check(check.as_str());
+        }
+        if let Value::Text(ref default) = row[4] {
+            // The pragma drops the parentheses of expression defaults.
+            col = match definition.keyword(&range, "DEFAULT") {
+                Some(i) if tokens.is_punct(i + 1, '(') => col.default_value(&format!("({})", default)),
+                _ => col.default_value(default)
+            };
+        }
+        if let Some(i) = definition.keyword(&range, "COLLATE") {
+            if let Some(collation) = tokens.name(i + 1) {
+                col = col.collate(&collation);
+            }
+        }
+        if matches!(int(&row[6]), 2 | 3) {
+            let expr = definition.keyword(&range, "AS").map_or("", |i| definition.parenthesized(i + 1));
+            let typ = if int(&row[6]) == 3 { GeneratedColumnType::Stored } else { GeneratedColumnType::Virtual };
+            col = col.generated(expr, typ);
+        }
+        table.add_column(col);
+    }
+    if !primary_key.is_empty() && !rowid_alias {
+        table.primary_key(&primary_key.iter().map(|k| k.1).collect::<Vec<&str>>());
+    }
+
+    let indexes = pragma(db, "index_list", &object.name)?;
+    for row in indexes.iter().rev().filter(|r| r.len() >= 5) {
+        let name = text(&row[1]);
+        match text(&row[3]) {
+            "u" => {
+                let info = pragma(db, "index_xinfo", name)?;
+                let columns = info.iter().filter(|r| r.len() >= 6 && int(&r[5]) == 1).map(|r| text(&r[2])).collect::<Vec<&str>>();
+                table.add_unique(&columns);
+            },
+            "c" => if let Some(object) = objects.iter().find(|o| o.typ == "index" && o.name == name) {
+                if let parse::Object::Index { index, .. } = parse_object(object)? {
+                    table.add_index(index);
+                }
+            },
+            _ => {}
+        }
+    }
+    for range in definition.constraints() {
+        if let Some(check) = definition.checks(range) {
+            table.add_check(Check::new(check.as_str()));
+        }
+    }
+
+    let mut foreign_keys: Vec<(i64, Vec<&Vec<Value>>)> = Vec::new();
+    let references = pragma(db, "foreign_key_list", &object.name)?;
+    for row in references.iter().filter(|r| r.len() >= 8) {
+        match foreign_keys.iter_mut().find(|fk| fk.0 == int(&row[0])) {
+            Some(fk) => fk.1.push(row),
+            None => foreign_keys.push((int(&row[0]), vec![row]))
+        }
+    }
+    foreign_keys.sort_by_key(|fk| -fk.0);
+    let clauses = definition.references();
+    for (k, (_, rows)) in foreign_keys.into_iter().enumerate() {
+        let from = rows.iter().map(|r| text(&r[3])).collect::<Vec<&str>>();
+        let to = rows.iter().filter(|r| r[4] != Value::Null).map(|r| text(&r[4])).collect::<Vec<&str>>();
+        let mut fk = ForeignKey::with_columns(&from).references_columns(text(&rows[0][2]), &to);
+        if let Some(action) = action(text(&rows[0][5])) {
+            fk = fk.on_update(action);
+        }
+        if let Some(action) = action(text(&rows[0][6])) {
+            fk = fk.on_delete(action);
+        }
+        match text(&rows[0][7]) {
+            "SIMPLE" => fk = fk.match_(ForeignKeyMatch::Simple),
+            "PARTIAL" => fk = fk.match_(ForeignKeyMatch::Partial),
+            "FULL" => fk = fk.match_(ForeignKeyMatch::Full),
+            _ => {}
+        }
+        if let Some(clause) = clauses.get(k) {
+            let deferred = tokens.find(clause.clone(), |i| tokens.are_keywords(i, &["INITIALLY", "DEFERRED"])).is_some();
+            let not = tokens.find(clause.clone(), |i| tokens.are_keywords(i, &["NOT", "DEFERRABLE"])).is_some();
+            if deferred && !not {
+                fk = fk.initially_deferred();
+            }
+        }
+        table.add_foreign_key(fk);
+    }
+    if strict {
+        table.strict();
+    }
+    if without_rowid {
+        table.without_rowid();
+    }
+    Ok(Some(table))
+}
+
+/// Foreign key action as reported by `foreign_key_list`, `None` for the default `NO ACTION`.
+fn action(name: &str) -> Option<ForeignKeyAction> {
+    match name {
+        "SET NULL" => Some(ForeignKeyAction::SetNull),
+        "SET DEFAULT" => Some(ForeignKeyAction::SetDefault),
+        "RESTRICT" => Some(ForeignKeyAction::Restrict),
+        "CASCADE" => Some(ForeignKeyAction::Cascade),
+        _ => None
+    }
+}
+
+/// Column definitions and table constraints of `CREATE TABLE` SQL.
+struct TableSql<'t, 'a> {
+    tokens: &'t Tokens<'a>,
+    parts: Vec<Range<usize>>
+}
+
+impl<'t, 'a> TableSql<'t, 'a> {
+    fn new(tokens: &'t Tokens<'a>) -> Self {
+        let parts = match tokens.find(0..tokens.len(), |i| tokens.is_punct(i, '(')) {
+            Some(open) => tokens.split(open + 1..tokens.closing(open), ','),
+            None => Vec::new()
+        };
+        Self { tokens, parts }
+    }
+
+    fn is_constraint(&self, part: &Range<usize>) -> bool {
+        ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"].iter().any(|k| self.tokens.is_keyword(part.start, k))
+    }
+
+    fn column(&self, name: &str) -> Option<Range<usize>> {
+        self.parts.iter()
+            .find(|p| !self.is_constraint(p) && self.tokens.name(p.start).is_some_and(|n| n.eq_ignore_ascii_case(name)))
+            .map(|p| p.start + 1..p.end)
+    }
+
+    fn constraints(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.parts.iter().filter(|p| self.is_constraint(p)).cloned()
+    }
+
+    /// `REFERENCES` clauses in the order of declaration, which is the order of the foreign keys.
+    fn references(&self) -> Vec<Range<usize>> {
+        let mut clauses = Vec::new();
+        for part in &self.parts {
+            let mut start = self.keyword(part, "REFERENCES");
+            while let Some(i) = start {
+                start = self.keyword(&(i + 1..part.end), "REFERENCES");
+                clauses.push(i..start.unwrap_or(part.end));
+            }
+        }
+        clauses
+    }
+
+    fn keyword(&self, range: &Range<usize>, keyword: &str) -> Option<usize> {
+        self.tokens.find(range.clone(), |i| self.tokens.is_keyword(i, keyword))
+    }
+
+    /// SQL inside the parentheses starting at `i`.
+    fn parenthesized(&self, i: usize) -> &'a str {
+        match self.tokens.is_punct(i, '(') {
+            true => self.tokens.text(i + 1..self.tokens.closing(i)),
+            false => ""
+        }
+    }
+
+    /// Expressions of the `CHECK` constraints in the range, joined with `AND`.
+    fn checks(&self, range: Range<usize>) -> Option<String> {
+        let mut checks = Vec::new();
+        let mut start = range.start;
+        while let Some(i) = self.keyword(&(start..range.end), "CHECK") {
+            checks.push(self.parenthesized(i + 1));
+            start = i + 1;
+        }
+        match checks.as_slice() {
+            [] => None,
+            [check] => Some(check.to_string()),
+            checks => Some(checks.iter().map(|c| format!("({})", c)).collect::<Vec<String>>().join(" AND "))
+        }
+    }
+}
+
+/// View, trigger or index created by the SQL of the object.
+fn parse_object<E>(object: &Object) -> Result<parse::Object, Error<E>> {
+    parse::statement(&object.sql).map_err(|_| Error::Unsupported { object: object.name.clone(), sql: object.sql.clone() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read, Error};
+    use crate::Statement;
+
+    const SCHEMA: &str = "
+        CREATE TABLE users (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            email TEXT NOT NULL COLLATE NOCASE UNIQUE CHECK (email LIKE '%@%'),
+            name TEXT DEFAULT 'anonymous', -- comment
+            age INT CHECK(age >= 0) CHECK(age < 150),
+            created TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        );
+        CREATE TABLE posts (
+            id INTEGER PRIMARY KEY,
+            author INTEGER REFERENCES users(id) ON DELETE CASCADE,
+            title TEXT,
+            slug TEXT GENERATED ALWAYS AS (lower(title)) STORED,
+            words INT AS (length(title)),
+            [the rank] REAL DEFAULT (0.5 * 2),
+            CONSTRAINT positive CHECK (id > 0)
+        );
+        CREATE TABLE tags (post INTEGER, name TEXT, PRIMARY KEY (post, name), FOREIGN KEY (post) REFERENCES posts DEFERRABLE INITIALLY DEFERRED) WITHOUT ROWID, STRICT;
+        CREATE INDEX idx_posts_title ON posts (title COLLATE NOCASE DESC, (lower(slug))) WHERE title IS NOT NULL;
+        CREATE UNIQUE INDEX idx_users_name ON users (name);
+        CREATE VIEW titles (id, title) AS SELECT id, title FROM posts WHERE title <> '';
+        CREATE TRIGGER posts_insert AFTER INSERT ON posts FOR EACH ROW WHEN new.title IS NULL
+        BEGIN
+            UPDATE posts SET title = 'untitled' WHERE id = new.id;
+            SELECT CASE WHEN new.id < 0 THEN RAISE(ABORT, 'negative; id') END;
+        END;";
+
+    #[test]
+    fn round_trip() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        let db = read(&mut conn).unwrap();
+        let script = db.try_to_sql().unwrap();
+        assert!(script.starts_with("CREATE TABLE IF NOT EXISTS users (\n\
+            id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,\n\
+            email TEXT NOT NULL CHECK(email LIKE '%@%') COLLATE NOCASE,\n\
+            name TEXT DEFAULT 'anonymous',\n\
+            age INT CHECK((age >= 0) AND (age < 150)),\n\
+            created TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,\n\
+            UNIQUE (email)\n\
+            );\n\
+            CREATE UNIQUE INDEX idx_users_name ON users (name);\n"), "{}", script);
+        assert!(script.contains("slug TEXT AS (lower(title)) STORED,\nwords INT AS (length(title)) VIRTUAL,\n\"the rank\" REAL DEFAULT (0.5 * 2),\n CHECK(id > 0) ,\n\
+            FOREIGN KEY (author) REFERENCES users (id) ON DELETE CASCADE\n);"), "{}", script);
+        assert!(script.contains("CREATE INDEX idx_posts_title ON posts (title COLLATE NOCASE DESC, (lower(slug))) WHERE title IS NOT NULL;"), "{}", script);
+        assert!(script.contains("PRIMARY KEY (post, name),\nFOREIGN KEY (post) REFERENCES posts DEFERRABLE INITIALLY DEFERRED\n) STRICT, WITHOUT ROWID;"), "{}", script);
+        assert!(script.contains("CREATE VIEW IF NOT EXISTS titles(id, title) AS SELECT id, title FROM posts WHERE title <> '';"), "{}", script);
+        assert!(script.contains("WHEN new.title IS NULL\nBEGIN\nUPDATE posts SET title = 'untitled' WHERE id = new.id;\n\
+            SELECT CASE WHEN new.id < 0 THEN RAISE(ABORT, 'negative; id') END;\nEND;"), "{}", script);
+
+        let mut copy = rusqlite::Connection::open_in_memory().unwrap();
+        copy.execute_batch(&script).unwrap();
+        assert_eq!(read(&mut copy).unwrap().to_string(), script);
+    }
+
+    #[test]
+    fn unsupported() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE t (a); CREATE TRIGGER t_a AFTER UPDATE OF a ON t BEGIN SELECT 1; END;").unwrap();
+        assert!(matches!(read(&mut conn), Err(Error::Unsupported { ref object, .. }) if object == "t_a"));
+    }
+}
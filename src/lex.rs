@@ -0,0 +1,194 @@
+//! Tokens of SQL text, to read back the SQL of schema objects.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    /// Keyword or bare identifier.
+    Word,
+    /// `"name"`, `` `name` `` or `[name]`.
+    Quoted,
+    /// `'text'` or `x'00ff'`.
+    String,
+    Number,
+    /// Any other character, eg. `(` or `;`.
+    Punct(char)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    kind: Kind,
+    start: usize,
+    end: usize
+}
+
+/// SQL split into tokens, comments and white space are skipped.
+pub(crate) struct Tokens<'a> {
+    sql: &'a str,
+    tokens: Vec<Token>
+}
+
+impl<'a> Tokens<'a> {
+    pub(crate) fn new(sql: &'a str) -> Self {
+        let bytes = sql.as_bytes();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        // Index of the first byte of `close` after `from`, the end of the SQL when there is none.
+        let find = |from: usize, close: &str| sql[from..].find(close).map_or(sql.len(), |p| from + p);
+        while i < bytes.len() {
+            let start = i;
+            let kind = match bytes[i] {
+                b if b.is_ascii_whitespace() => {
+                    i += 1;
+                    continue;
+                },
+                b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                    i = find(i, "\n");
+                    continue;
+                },
+                b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                    i = (find(i + 2, "*/") + 2).min(sql.len());
+                    continue;
+                },
+                b'\'' => {
+                    i = quoted_end(bytes, i, b'\'');
+                    Kind::String
+                },
+                b'x' | b'X' if bytes.get(i + 1) == Some(&b'\'') => {
+                    i = quoted_end(bytes, i + 1, b'\'');
+                    Kind::String
+                },
+                b'"' | b'`' => {
+                    i = quoted_end(bytes, i, bytes[i]);
+                    Kind::Quoted
+                },
+                b'[' => {
+                    i = (find(i, "]") + 1).min(sql.len());
+                    Kind::Quoted
+                },
+                b if b.is_ascii_digit() || (b == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)) => {
+                    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                        i += if matches!(bytes[i], b'e' | b'E') && matches!(bytes.get(i + 1), Some(b'+' | b'-')) { 2 } else { 1 };
+                    }
+                    Kind::Number
+                },
+                b if b.is_ascii_alphabetic() || b == b'_' || b >= 0x80 => {
+                    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b'$' || bytes[i] >= 0x80) {
+                        i += 1;
+                    }
+                    Kind::Word
+                },
+                b => {
+                    i += 1;
+                    Kind::Punct(b as char)
+                }
+            };
+            tokens.push(Token { kind, start, end: i });
+        }
+        Self { sql, tokens }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub(crate) fn kind(&self, i: usize) -> Option<Kind> {
+        self.tokens.get(i).map(|t| t.kind)
+    }
+
+    pub(crate) fn is_keyword(&self, i: usize, keyword: &str) -> bool {
+        self.tokens.get(i).is_some_and(|t| t.kind == Kind::Word && self.sql[t.start..t.end].eq_ignore_ascii_case(keyword))
+    }
+
+    /// Whether the tokens from `i` are the given keywords.
+    pub(crate) fn are_keywords(&self, i: usize, keywords: &[&str]) -> bool {
+        keywords.iter().enumerate().all(|(j, k)| self.is_keyword(i + j, k))
+    }
+
+    pub(crate) fn is_punct(&self, i: usize, c: char) -> bool {
+        self.kind(i) == Some(Kind::Punct(c))
+    }
+
+    /// Name given by an identifier, a keyword or a string, without the quotes.
+    pub(crate) fn name(&self, i: usize) -> Option<String> {
+        let token = self.tokens.get(i)?;
+        let text = &self.sql[token.start..token.end];
+        match token.kind {
+            Kind::Word => Some(text.to_owned()),
+            Kind::Quoted if text.starts_with('[') => Some(text[1..text.len() - 1].to_owned()),
+            Kind::Quoted | Kind::String if text.len() >= 2 => {
+                let quote = &text[..1];
+                Some(text[1..text.len() - 1].replace(&quote.repeat(2), quote))
+            },
+            _ => None
+        }
+    }
+
+    /// SQL of the tokens in the range, as written.
+    pub(crate) fn text(&self, range: Range<usize>) -> &'a str {
+        match (self.tokens.get(range.start), range.end.checked_sub(1).and_then(|e| self.tokens.get(e))) {
+            (Some(first), Some(last)) if range.start < range.end => &self.sql[first.start..last.end],
+            _ => ""
+        }
+    }
+
+    /// Index of the `)` closing the `(` at `i`, the end of the tokens when it is not closed.
+    pub(crate) fn closing(&self, i: usize) -> usize {
+        let mut depth = 0;
+        for j in i..self.tokens.len() {
+            match self.tokens[j].kind {
+                Kind::Punct('(') => depth += 1,
+                Kind::Punct(')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return j;
+                    }
+                },
+                _ => {}
+            }
+        }
+        self.tokens.len()
+    }
+
+    /// Index of the first token in the range matching `f` outside of parentheses.
+    pub(crate) fn find(&self, range: Range<usize>, f: impl Fn(usize) -> bool) -> Option<usize> {
+        let mut i = range.start;
+        while i < range.end.min(self.tokens.len()) {
+            if f(i) {
+                return Some(i);
+            }
+            i = if self.is_punct(i, '(') { self.closing(i) + 1 } else { i + 1 };
+        }
+        None
+    }
+
+    /// The range split at the given punctuation outside of parentheses, empty parts are skipped.
+    pub(crate) fn split(&self, range: Range<usize>, c: char) -> Vec<Range<usize>> {
+        let mut parts = Vec::new();
+        let mut start = range.start;
+        let end = range.end.min(self.tokens.len());
+        while let Some(at) = self.find(start..end, |i| self.is_punct(i, c)) {
+            parts.push(start..at);
+            start = at + 1;
+        }
+        parts.push(start..end);
+        parts.retain(|r| r.start < r.end);
+        parts
+    }
+}
+
+/// End of a token quoted with `quote` starting at `i`, a doubled quote is part of the token.
+fn quoted_end(bytes: &[u8], i: usize, quote: u8) -> usize {
+    let mut j = i + 1;
+    while j < bytes.len() {
+        if bytes[j] == quote {
+            if bytes.get(j + 1) == Some(&quote) {
+                j += 2;
+                continue;
+            }
+            return j + 1;
+        }
+        j += 1;
+    }
+    bytes.len()
+}
@@ -1,2 +1,22 @@
+pub mod expr;
 pub mod query;
-pub mod schema;
\ No newline at end of file
+pub mod schema;
+pub mod migrate;
+#[cfg(feature = "parse")]
+pub mod parse;
+#[cfg(feature = "introspect")]
+pub mod introspect;
+mod value;
+mod statement;
+mod ident;
+mod conflict;
+mod order;
+mod error;
+#[cfg(feature = "parse")]
+mod lex;
+
+pub use value::{Value, Placeholder};
+pub use ident::{Ident, is_keyword, text_literal, blob_literal};
+pub use statement::Statement;
+pub use conflict::ConflictResolution;
+pub use error::Error;
\ No newline at end of file
@@ -0,0 +1,580 @@
+//! Versioned schema migrations.
+//!
+//! A [`Migration`] is a list of statements with an id. A [`Migrator`] applies the pending ones in order,
+//! each in its own transaction, and records them in a bookkeeping table or in `PRAGMA user_version`.
+//! The bookkeeping table also stores a checksum of every migration, so migrations edited after they
+//! were applied are detected.
+
+use std::fmt;
+
+use crate::expr::{col, lit};
+use crate::query::{select, insert};
+use crate::schema::{create_table, Column};
+use crate::{Statement, Value, Ident};
+
+/// Default name of the bookkeeping table.
+pub const MIGRATIONS_TABLE: &str = "_yaqub_migrations";
+
+/// Connection the migrations are run with. It is implemented for `rusqlite::Connection` by the `rusqlite` feature.
+pub trait Executor {
+    type Error;
+
+    /// Executes statements separated by `;`.
+    fn execute_batch(&mut self, sql: &str) -> Result<(), Self::Error>;
+
+    /// Rows returned by a query, values in column order.
+    fn query(&mut self, sql: &str) -> Result<Vec<Vec<Value>>, Self::Error>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error<E> {
+    /// Error returned by the [`Executor`].
+    Database(E),
+    /// A statement of a migration is not valid.
+    Statement { migration: String, error: crate::Error },
+    /// Two migrations with the same id.
+    DuplicateMigration { id: String },
+    /// A `PRAGMA foreign_key_check` of a migration, eg. the one of a table rebuild, found rows of `table`
+    /// violating a foreign key. The migration was rolled back.
+    ForeignKeyViolation { id: String, table: String },
+    /// A migration run in a transaction has a statement with its own, eg. a table rebuild, see
+    /// [`Migration::without_transaction`].
+    NestedTransaction { id: String },
+    /// An applied migration was edited afterwards.
+    ChecksumMismatch { id: String },
+    /// The database records an applied migration which is not defined, or a version past the last migration.
+    UnknownMigration { id: String },
+    /// The database records the migration `found` where `expected` comes in the defined order.
+    Diverged { expected: String, found: String }
+}
+
+impl<E> fmt::Display for Error<E> where E: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Database(e) => write!(f, "database error: {}", e),
+            Self::Statement { migration, error } => write!(f, "invalid statement in migration {}: {}", migration, error),
+            Self::DuplicateMigration { id } => write!(f, "migration {} is defined more than once", id),
+            Self::ForeignKeyViolation { id, table } => write!(f, "migration {} leaves rows of {} violating a foreign key", id, table),
+            Self::NestedTransaction { id } => write!(f, "migration {} has a statement with its own transaction, it must run without one", id),
+            Self::ChecksumMismatch { id } => write!(f, "migration {} was changed after it was applied", id),
+            Self::UnknownMigration { id } => write!(f, "applied migration {} is not defined", id),
+            Self::Diverged { expected, found } => write!(f, "migration {} is applied where {} was expected", found, expected)
+        }
+    }
+}
+
+impl<E> std::error::Error for Error<E> where E: fmt::Debug + fmt::Display {}
+
+/// Statements applied together, identified by an id such as `"0001_create_users"`.
+#[derive(Debug, Clone)]
+pub struct Migration {
+    id: String,
+    statements: Vec<Result<String, crate::Error>>,
+    transaction: bool,
+    nested_transaction: bool
+}
+
+impl Migration {
+    pub fn new(id: &str) -> Self {
+        Self { id: id.to_owned(), statements: Vec::new(), transaction: true, nested_transaction: false }
+    }
+
+    /// Runs the statements outside of a transaction, for scripts managing their own, such as table
+    /// rebuilds of [`diff_tables`](crate::schema::diff_tables) which turn foreign keys off first.
+    /// The migration is recorded after its statements ran. Migrations with such yaqub statements
+    /// are rejected unless they run without a transaction.
+    pub fn without_transaction(mut self) -> Self {
+        self.transaction = false;
+        self
+    }
+
+    /// Adds a yaqub statement, it is validated when the migration is applied.
+    pub fn statement(mut self, statement: &impl Statement) -> Self {
+        self.nested_transaction |= statement.has_transaction();
+        self.statements.push(statement.try_to_sql());
+        self
+    }
+
+    /// Adds raw SQL, eg. a data migration.
+    pub fn sql(mut self, sql: &str) -> Self {
+        self.statements.push(Ok(sql.to_owned()));
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// SQL of the migration, the statements separated by new lines.
+    pub fn script(&self) -> Result<String, crate::Error> {
+        let statements = self.statements.iter().cloned().collect::<Result<Vec<String>, crate::Error>>()?;
+        Ok(statements.join("\n"))
+    }
+
+    /// FNV-1a hash of the SQL, as 16 hex digits.
+    pub fn checksum(&self) -> String {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for statement in &self.statements {
+            let sql = match statement {
+                Ok(sql) => sql.clone(),
+                Err(e) => e.to_string()
+            };
+            for byte in sql.bytes().chain(std::iter::once(0)) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        format!("{:016x}", hash)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Tracking {
+    Table(Ident),
+    UserVersion
+}
+
+/// Ordered migrations and the way applied ones are recorded.
+#[derive(Debug, Clone)]
+pub struct Migrator {
+    migrations: Vec<Migration>,
+    tracking: Tracking
+}
+
+impl Default for Migrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Migrator {
+    /// Migrations are recorded in the [`MIGRATIONS_TABLE`] table.
+    pub fn new() -> Self {
+        Self { migrations: Vec::new(), tracking: Tracking::Table(Ident::new(MIGRATIONS_TABLE)) }
+    }
+
+    pub fn add(&mut self, migration: Migration) -> &mut Self {
+        self.migrations.push(migration);
+        self
+    }
+
+    /// Records migrations in the given bookkeeping table.
+    pub fn table(&mut self, name: &str) -> &mut Self {
+        self.tracking = Tracking::Table(Ident::new(name));
+        self
+    }
+
+    /// Records the number of applied migrations in `PRAGMA user_version`, without checksums.
+    pub fn user_version(&mut self) -> &mut Self {
+        self.tracking = Tracking::UserVersion;
+        self
+    }
+
+    /// Migrations not applied to the database yet, after checking the applied ones.
+    pub fn pending<E>(&self, db: &mut E) -> Result<&[Migration], Error<E::Error>> where E: Executor {
+        self.check()?;
+        let applied = self.applied(db)?;
+        Ok(&self.migrations[applied..])
+    }
+
+    /// Applies the pending migrations, each in a transaction, and returns their ids. A migration is rolled
+    /// back when it fails or when one of its `PRAGMA foreign_key_check` returns violations. When a migration
+    /// turns foreign keys off, eg. to rebuild a table, their previous setting is restored afterwards.
+    pub fn migrate<E>(&self, db: &mut E) -> Result<Vec<String>, Error<E::Error>> where E: Executor {
+        let pending = self.pending(db)?;
+        if let Tracking::Table(ref table) = self.tracking {
+            db.execute_batch(&bookkeeping_table(table)).map_err(Error::Database)?;
+        }
+        let mut ids = Vec::new();
+        for migration in pending {
+            let sql = self.transaction(migration)?;
+            let restore = restore_foreign_keys(db, &sql)?;
+            if let Err(e) = execute(db, &migration.id, &sql) {
+                let _ = db.execute_batch("ROLLBACK;");
+                if let Some(ref restore) = restore {
+                    let _ = db.execute_batch(restore);
+                }
+                return Err(e);
+            }
+            if let Some(ref restore) = restore {
+                db.execute_batch(restore).map_err(Error::Database)?;
+            }
+            ids.push(migration.id.clone());
+        }
+        Ok(ids)
+    }
+
+    /// SQL [`migrate`](Migrator::migrate) would execute, the database is only read.
+    pub fn dry_run<E>(&self, db: &mut E) -> Result<String, Error<E::Error>> where E: Executor {
+        let pending = self.pending(db)?;
+        let mut script = Vec::new();
+        if let Tracking::Table(ref table) = self.tracking {
+            if !pending.is_empty() {
+                script.push(bookkeeping_table(table));
+            }
+        }
+        for migration in pending {
+            let sql = self.transaction(migration)?;
+            let restore = restore_foreign_keys(db, &sql)?;
+            script.push(sql);
+            script.extend(restore);
+        }
+        Ok(script.join("\n"))
+    }
+
+    fn check<E>(&self) -> Result<(), Error<E>> {
+        for (i, migration) in self.migrations.iter().enumerate() {
+            if self.migrations[..i].iter().any(|m| m.id == migration.id) {
+                return Err(Error::DuplicateMigration { id: migration.id.clone() });
+            }
+            if migration.transaction && migration.nested_transaction {
+                return Err(Error::NestedTransaction { id: migration.id.clone() });
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of applied migrations.
+    fn applied<E>(&self, db: &mut E) -> Result<usize, Error<E::Error>> where E: Executor {
+        let table = match self.tracking {
+            Tracking::Table(ref table) => table,
+            Tracking::UserVersion => {
+                let rows = db.query("PRAGMA user_version;").map_err(Error::Database)?;
+                let version = match rows.first().and_then(|r| r.first()) {
+                    Some(Value::Integer(v)) => *v as usize,
+                    _ => 0
+                };
+                if version > self.migrations.len() {
+                    return Err(Error::UnknownMigration { id: format!("version {}", version) });
+                }
+                return Ok(version);
+            }
+        };
+        let exists = select("name").from("sqlite_schema")
+            .where_(col("type").eq(lit("table")).and(col("name").eq(lit(table.as_str()))))
+            .to_string();
+        if db.query(&exists).map_err(Error::Database)?.is_empty() {
+            return Ok(0);
+        }
+        let query = select(&["id", "checksum"]).from(table.as_str()).order_by("rowid").to_string();
+        let rows = db.query(&query).map_err(Error::Database)?;
+        for (i, row) in rows.iter().enumerate() {
+            let (id, checksum) = match row.as_slice() {
+                [Value::Text(id), Value::Text(checksum)] => (id, checksum),
+                _ => continue
+            };
+            let migration = match self.migrations.get(i) {
+                Some(m) => m,
+                None => return Err(Error::UnknownMigration { id: id.clone() })
+            };
+            if migration.id != *id {
+                return match self.migrations.iter().any(|m| m.id == *id) {
+                    true => Err(Error::Diverged { expected: migration.id.clone(), found: id.clone() }),
+                    false => Err(Error::UnknownMigration { id: id.clone() })
+                };
+            }
+            if migration.checksum() != *checksum {
+                return Err(Error::ChecksumMismatch { id: id.clone() });
+            }
+        }
+        Ok(rows.len())
+    }
+
+    /// Statements of the migration and its bookkeeping, wrapped in a transaction unless the migration has its own.
+    fn transaction<E>(&self, migration: &Migration) -> Result<String, Error<E>> {
+        let script = migration.script().map_err(|error| Error::Statement { migration: migration.id.clone(), error })?;
+        let record = match self.tracking {
+            Tracking::Table(ref table) => insert(&["id", "checksum"])
+                .into(table.as_str())
+                .values(&[lit(migration.id.as_str()), lit(migration.checksum())])
+                .to_string() + ";",
+            Tracking::UserVersion => {
+                let version = self.migrations.iter().position(|m| m.id == migration.id).unwrap_or_default() + 1;
+                format!("PRAGMA user_version = {};", version)
+            }
+        };
+        match migration.transaction {
+            true => Ok(format!("BEGIN;\n{}\n{}\nCOMMIT;", script, record)),
+            false => Ok(format!("{}\n{}", script, record))
+        }
+    }
+}
+
+/// Executes the script of a migration, stopping when one of its `PRAGMA foreign_key_check` returns violations.
+fn execute<E>(db: &mut E, id: &str, sql: &str) -> Result<(), Error<E::Error>> where E: Executor {
+    let mut batch = Vec::new();
+    for line in sql.lines() {
+        if !line.starts_with("PRAGMA foreign_key_check") {
+            batch.push(line);
+            continue;
+        }
+        if !batch.is_empty() {
+            db.execute_batch(&batch.join("\n")).map_err(Error::Database)?;
+            batch.clear();
+        }
+        if let Some(row) = db.query(line).map_err(Error::Database)?.first() {
+            let table = match row.first() {
+                Some(Value::Text(table)) => table.clone(),
+                _ => String::new()
+            };
+            return Err(Error::ForeignKeyViolation { id: id.to_owned(), table });
+        }
+    }
+    match batch.is_empty() {
+        true => Ok(()),
+        false => db.execute_batch(&batch.join("\n")).map_err(Error::Database)
+    }
+}
+
+/// `PRAGMA foreign_keys` statement restoring the current setting, when the script turns foreign keys off.
+fn restore_foreign_keys<E>(db: &mut E, sql: &str) -> Result<Option<String>, Error<E::Error>> where E: Executor {
+    if !sql.contains("PRAGMA foreign_keys = OFF;") {
+        return Ok(None);
+    }
+    let rows = db.query("PRAGMA foreign_keys;").map_err(Error::Database)?;
+    let on = matches!(rows.first().and_then(|r| r.first()), Some(Value::Integer(1)));
+    Ok(Some(format!("PRAGMA foreign_keys = {};", if on { "ON" } else { "OFF" })))
+}
+
+fn bookkeeping_table(name: &Ident) -> String {
+    let mut table = create_table(name.as_str());
+    table.add_column(Column::new("id").text().primary_key())
+        .add_column(Column::new("checksum").text())
+        .add_column(Column::new("applied_at").text().default_value("CURRENT_TIMESTAMP"));
+    table.to_string()
+}
+
+#[cfg(feature = "rusqlite")]
+impl Executor for rusqlite::Connection {
+    type Error = rusqlite::Error;
+
+    fn execute_batch(&mut self, sql: &str) -> Result<(), Self::Error> {
+        rusqlite::Connection::execute_batch(self, sql)
+    }
+
+    fn query(&mut self, sql: &str) -> Result<Vec<Vec<Value>>, Self::Error> {
+        use rusqlite::types::ValueRef;
+
+        let mut statement = self.prepare(sql)?;
+        let count = statement.column_count();
+        let rows = statement.query_map([], |row| {
+            (0..count).map(|i| Ok(match row.get_ref(i)? {
+                ValueRef::Null => Value::Null,
+                ValueRef::Integer(v) => Value::Integer(v),
+                ValueRef::Real(v) => Value::Real(v),
+                ValueRef::Text(v) => Value::Text(String::from_utf8_lossy(v).into_owned()),
+                ValueRef::Blob(v) => Value::Blob(v.to_vec())
+            })).collect()
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Executor, Migration, Migrator, Error};
+    use crate::schema::{create_table, create_index, alter_table, Column};
+    use crate::Statement;
+    use crate::Value;
+
+    /// Records executed SQL and answers queries with canned rows.
+    #[derive(Default)]
+    struct Recorder {
+        executed: Vec<String>,
+        tables: Vec<Vec<Value>>,
+        applied: Vec<Vec<Value>>,
+        violations: Vec<Vec<Value>>,
+        foreign_keys: bool,
+        version: i64
+    }
+
+    impl Executor for Recorder {
+        type Error = String;
+
+        fn execute_batch(&mut self, sql: &str) -> Result<(), String> {
+            if sql.contains("fail") {
+                return Err("syntax error".to_owned());
+            }
+            self.executed.push(sql.to_owned());
+            Ok(())
+        }
+
+        fn query(&mut self, sql: &str) -> Result<Vec<Vec<Value>>, String> {
+            if sql.starts_with("PRAGMA foreign_key_check") {
+                Ok(self.violations.clone())
+            } else if sql.starts_with("PRAGMA foreign_keys") {
+                Ok(vec![vec![Value::Integer(self.foreign_keys as i64)]])
+            } else if sql.starts_with("PRAGMA") {
+                Ok(vec![vec![Value::Integer(self.version)]])
+            } else if sql.contains("sqlite_schema") {
+                Ok(self.tables.clone())
+            } else {
+                Ok(self.applied.clone())
+            }
+        }
+    }
+
+    fn migrations() -> Migrator {
+        let mut users = create_table("users");
+        users.add_column(Column::new("id").primary_key());
+        let mut email = alter_table("users");
+        email.add_column(Column::new("email").text().nullable());
+        let mut migrator = Migrator::new();
+        migrator.add(Migration::new("0001_users").statement(&users))
+            .add(Migration::new("0002_email").statement(&email).statement(&create_index("idx_email").on("users").column("email")));
+        migrator
+    }
+
+    #[test]
+    fn migrate() {
+        let mut db = Recorder::default();
+        assert_eq!(migrations().migrate(&mut db), Ok(vec!["0001_users".to_owned(), "0002_email".to_owned()]));
+        assert_eq!(db.executed.len(), 3);
+        assert!(db.executed[0].starts_with("CREATE TABLE IF NOT EXISTS _yaqub_migrations"));
+        assert_eq!(db.executed[2], format!("BEGIN;\nALTER TABLE users ADD COLUMN email TEXT;\nCREATE INDEX idx_email ON users (email);\n\
+            INSERT INTO _yaqub_migrations(id, checksum) VALUES('0002_email', '{}');\nCOMMIT;", migrations().migrations[1].checksum()));
+    }
+
+    #[test]
+    fn applied() {
+        let migrator = migrations();
+        let checksum = migrator.migrations[0].checksum();
+        let mut db = Recorder {
+            tables: vec![vec![Value::Text("_yaqub_migrations".into())]],
+            applied: vec![vec![Value::Text("0001_users".into()), Value::Text(checksum)]],
+            ..Recorder::default()
+        };
+        assert_eq!(migrator.pending(&mut db).map(|p| p.len()), Ok(1));
+        let sql = migrator.dry_run(&mut db).unwrap();
+        assert!(sql.contains("ALTER TABLE users ADD COLUMN email TEXT;") && !sql.contains("CREATE TABLE users"));
+        assert!(db.executed.is_empty());
+
+        db.applied[0][1] = Value::Text("0".into());
+        assert_eq!(migrator.pending(&mut db).unwrap_err(), (Error::ChecksumMismatch { id: "0001_users".into() }));
+        db.applied[0][0] = Value::Text("0000_init".into());
+        assert_eq!(migrator.pending(&mut db).unwrap_err(), (Error::UnknownMigration { id: "0000_init".into() }));
+        db.applied[0][0] = Value::Text("0002_email".into());
+        assert_eq!(migrator.pending(&mut db).unwrap_err(), (Error::Diverged { expected: "0001_users".into(), found: "0002_email".into() }));
+    }
+
+    #[test]
+    fn user_version() {
+        let mut migrator = migrations();
+        migrator.user_version();
+        let mut db = Recorder { version: 1, ..Recorder::default() };
+        assert_eq!(migrator.migrate(&mut db), Ok(vec!["0002_email".to_owned()]));
+        assert_eq!(db.executed.len(), 1);
+        assert!(db.executed[0].ends_with("PRAGMA user_version = 2;\nCOMMIT;"));
+
+        db.version = 3;
+        assert_eq!(migrator.pending(&mut db).unwrap_err(), (Error::UnknownMigration { id: "version 3".into() }));
+
+        migrator.add(Migration::new("0003_rebuild").sql("BEGIN;\nCOMMIT;").without_transaction());
+        let mut db = Recorder { version: 2, ..Recorder::default() };
+        assert_eq!(migrator.dry_run(&mut db).unwrap(), "BEGIN;\nCOMMIT;\nPRAGMA user_version = 3;");
+    }
+
+    #[test]
+    fn invalid() {
+        let mut migrator = migrations();
+        migrator.add(Migration::new("0001_users"));
+        assert_eq!(migrator.migrate(&mut Recorder::default()), Err(Error::DuplicateMigration { id: "0001_users".into() }));
+
+        let mut migrator = Migrator::new();
+        migrator.add(Migration::new("bad").statement(&create_table("empty")));
+        let err = migrator.migrate(&mut Recorder::default()).unwrap_err();
+        assert_eq!(err, Error::Statement { migration: "bad".into(), error: crate::Error::NoColumns { table: "empty".into() } });
+
+        let mut users = create_table("users");
+        users.add_column(Column::new("id").primary_key()).add_column(Column::new("name").text());
+        let mut rebuild = users.alter();
+        rebuild.alter_column_type("name", "BLOB");
+        assert!(rebuild.has_transaction());
+        let mut migrator = Migrator::new();
+        migrator.add(Migration::new("0001_users").statement(&users)).add(Migration::new("0002_rebuild").statement(&rebuild));
+        let mut db = Recorder::default();
+        assert_eq!(migrator.migrate(&mut db), Err(Error::NestedTransaction { id: "0002_rebuild".into() }));
+        assert!(db.executed.is_empty());
+
+        let mut migrator = Migrator::new();
+        migrator.add(Migration::new("0001_rebuild").statement(&rebuild).without_transaction());
+        let mut db = Recorder { violations: vec![vec![Value::Text("orders".into()), Value::Integer(1)]], foreign_keys: true, ..Recorder::default() };
+        assert_eq!(migrator.migrate(&mut db), Err(Error::ForeignKeyViolation { id: "0001_rebuild".into(), table: "orders".into() }));
+        assert!(db.executed[1].starts_with("PRAGMA foreign_keys = OFF;\nBEGIN;") && db.executed[1].ends_with("PRAGMA legacy_alter_table = OFF;"));
+        assert_eq!(db.executed[2..], ["ROLLBACK;", "PRAGMA foreign_keys = ON;"]);
+
+        let mut db = Recorder::default();
+        assert_eq!(migrator.migrate(&mut db).map(|ids| ids.len()), Ok(1));
+        assert!(db.executed[2].starts_with("COMMIT;\nINSERT INTO _yaqub_migrations"));
+        assert_eq!(db.executed[3..], ["PRAGMA foreign_keys = OFF;"]);
+        assert!(migrator.dry_run(&mut Recorder::default()).unwrap().ends_with("PRAGMA foreign_keys = OFF;"));
+
+        let mut migrator = Migrator::new();
+        migrator.add(Migration::new("broken").sql("fail"));
+        assert_eq!(migrator.migrate(&mut Recorder::default()), Err(Error::Database("syntax error".into())));
+    }
+
+    #[cfg(feature = "rusqlite")]
+    #[test]
+    fn sqlite() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        let migrator = migrations();
+        assert_eq!(migrator.migrate(&mut conn).map(|ids| ids.len()), Ok(2));
+        assert_eq!(migrator.migrate(&mut conn).map(|ids| ids.len()), Ok(0));
+        assert_eq!(migrator.dry_run(&mut conn).as_deref(), Ok(""));
+        let count: i64 = conn.query_row("SELECT count(*) FROM _yaqub_migrations", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 2);
+
+        let mut users = create_table("users");
+        users.add_column(Column::new("id").primary_key());
+        let mut rebuild = users.alter();
+        rebuild.alter_column_type("id", "INT");
+        let mut migrator = migrations();
+        migrator.add(Migration::new("0003_rebuild").statement(&rebuild).without_transaction());
+        assert_eq!(migrator.migrate(&mut conn), Ok(vec!["0003_rebuild".to_owned()]));
+
+        let mut orders = create_table("orders");
+        orders.add_column(Column::new("id").primary_key()).add_column(Column::new("user_id").references(crate::schema::References::new("users", &["id"])));
+        conn.execute_batch(&orders.to_string()).unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = OFF; INSERT INTO orders VALUES(1, 42); PRAGMA foreign_keys = ON;").unwrap();
+        let mut rebuild = orders.alter();
+        rebuild.alter_column_type("user_id", "INT");
+        migrator.add(Migration::new("0004_orders").statement(&rebuild).without_transaction());
+        assert_eq!(migrator.migrate(&mut conn), Err(Error::ForeignKeyViolation { id: "0004_orders".into(), table: "orders".into() }));
+        let sql: String = conn.query_row("SELECT sql FROM sqlite_schema WHERE name = 'orders'", [], |r| r.get(0)).unwrap();
+        assert!(sql.contains("user_id INTEGER"));
+        let on: i64 = conn.query_row("PRAGMA foreign_keys", [], |r| r.get(0)).unwrap();
+        assert_eq!(on, 1);
+        assert!(conn.is_autocommit());
+    }
+
+    #[cfg(feature = "rusqlite")]
+    #[test]
+    fn sqlite_referenced() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        let mut users = create_table("users");
+        users.add_column(Column::new("id").primary_key()).add_column(Column::new("code").text().unique());
+        let mut orders = create_table("orders");
+        orders.add_column(Column::new("id").primary_key())
+            .add_column(Column::new("code").text().references(crate::schema::References::new("users", &["code"])));
+        let mut migrator = Migrator::new();
+        migrator.add(Migration::new("0001_users").statement(&users)).add(Migration::new("0002_orders").statement(&orders));
+        assert_eq!(migrator.migrate(&mut conn).map(|ids| ids.len()), Ok(2));
+        conn.execute_batch("INSERT INTO users VALUES(1, 'a'); INSERT INTO orders VALUES(1, 'a');").unwrap();
+
+        let mut rebuild = users.alter();
+        rebuild.drop_column("code");
+        migrator.add(Migration::new("0003_drop_code").statement(&rebuild).without_transaction());
+        assert!(matches!(migrator.migrate(&mut conn), Err(Error::Database(e)) if e.to_string().contains("foreign key mismatch")));
+        let sql: String = conn.query_row("SELECT sql FROM sqlite_schema WHERE name = 'users'", [], |r| r.get(0)).unwrap();
+        assert!(sql.contains("code TEXT"));
+        let off: i64 = conn.query_row("PRAGMA foreign_keys", [], |r| r.get(0)).unwrap();
+        assert_eq!(off, 0);
+        assert!(conn.is_autocommit());
+
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        assert!(matches!(migrator.migrate(&mut conn), Err(Error::Database(e)) if e.to_string().contains("foreign key mismatch")));
+        let on: i64 = conn.query_row("PRAGMA foreign_keys", [], |r| r.get(0)).unwrap();
+        assert_eq!(on, 1);
+    }
+}
@@ -0,0 +1,17 @@
+use std::fmt;
+
+/// Sort order of `ORDER BY` terms, index columns and primary keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SortOrder {
+    Asc,
+    Desc
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Asc => write!(f, "ASC"),
+            Self::Desc => write!(f, "DESC")
+        }
+    }
+}
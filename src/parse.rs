@@ -0,0 +1,780 @@
+//! Schema read from SQL text, with the `parse` feature.
+//!
+//! [`parse`] reads `CREATE TABLE`, `CREATE VIEW`, `CREATE TRIGGER` and `CREATE INDEX` statements, as
+//! written in a `.sql` file or stored in `sqlite_schema`, into a [`Database`] that can be validated,
+//! diffed and rendered again. Expressions, defaults and the bodies of views and triggers are kept as
+//! written.
+//!
+//! Nullable `PRIMARY KEY` and `UNIQUE` columns become table constraints, as yaqub makes key columns
+//! `NOT NULL`, and several `CHECK` constraints of a column are joined with `AND`. The names of table
+//! `CHECK` constraints are not kept.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::lex::{Kind, Tokens};
+use crate::schema::{create_index, create_table, create_trigger, create_view, Check, Column, Database, ForeignKey, ForeignKeyAction,
+    ForeignKeyMatch, GeneratedColumnType, Index, IndexedColumn, PrimaryKey, References, Table, TriggerFull, Unique, ViewCreateStmt};
+use crate::ConflictResolution;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Invalid SQL, near the given token.
+    Syntax { near: String, statement: String },
+    /// Valid SQL yaqub cannot represent, eg. `CREATE VIRTUAL TABLE` or an `UPDATE OF` trigger.
+    Unsupported { statement: String }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Syntax { near, statement } => write!(f, "syntax error near \"{}\": {}", near, statement),
+            Self::Unsupported { statement } => write!(f, "statement cannot be represented: {}", statement)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Schema object created by a statement.
+pub(crate) enum Object {
+    Table(Table),
+    View(ViewCreateStmt),
+    Trigger(TriggerFull),
+    Index { table: String, index: Index }
+}
+
+/// Reads a script of `CREATE` statements. Indexes are added to their table when it is created by
+/// the script, tables named `sqlite_*` are skipped.
+pub fn parse(sql: &str) -> Result<Database, Error> {
+    let tokens = Tokens::new(sql);
+    let mut tables: Vec<Table> = Vec::new();
+    let mut views = Vec::new();
+    let mut triggers = Vec::new();
+    let mut database = Database::new();
+    for range in statements(&tokens) {
+        match object(&tokens, range)? {
+            Object::Table(table) if table.name().to_ascii_lowercase().starts_with("sqlite_") => {}
+            Object::Table(table) => tables.push(table),
+            Object::View(view) => views.push(view),
+            Object::Trigger(trigger) => triggers.push(trigger),
+            Object::Index { table, index } => match tables.iter_mut().find(|t| t.name().eq_ignore_ascii_case(&table)) {
+                Some(t) => {
+                    t.add_index(index);
+                },
+                None => {
+                    database.add_index(index.on(&table));
+                }
+            }
+        }
+    }
+    tables.into_iter().for_each(|t| { database.add_table(t); });
+    views.into_iter().for_each(|v| { database.add_view(v); });
+    triggers.into_iter().for_each(|t| { database.add_trigger(t); });
+    Ok(database)
+}
+
+/// Reads a single `CREATE TABLE` statement.
+pub fn parse_table(sql: &str) -> Result<Table, Error> {
+    let tokens = Tokens::new(sql);
+    match statement(sql)? {
+        Object::Table(table) => Ok(table),
+        _ => Err(Parser::new(&tokens, 0..tokens.len()).error())
+    }
+}
+
+/// Object created by a single statement.
+pub(crate) fn statement(sql: &str) -> Result<Object, Error> {
+    let tokens = Tokens::new(sql);
+    match statements(&tokens).as_slice() {
+        [range] => object(&tokens, range.clone()),
+        [_, second, ..] => Err(Parser::new(&tokens, second.clone()).error()),
+        [] => Err(Parser::new(&tokens, 0..0).error())
+    }
+}
+
+/// Statements of a script, the `;` ending the statements inside a trigger do not end the trigger.
+fn statements(tokens: &Tokens) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        let create = start + if tokens.is_keyword(start + 1, "TEMP") || tokens.is_keyword(start + 1, "TEMPORARY") { 2 } else { 1 };
+        if i == start && tokens.is_keyword(start, "CREATE") && tokens.is_keyword(create, "TRIGGER") {
+            if let Some(begin) = tokens.find(i..tokens.len(), |j| tokens.is_keyword(j, "BEGIN")) {
+                // `CASE` expressions end with `END` too.
+                let mut depth = 0;
+                i = begin + 1;
+                while i < tokens.len() && !(depth == 0 && tokens.is_keyword(i, "END")) {
+                    if tokens.is_keyword(i, "CASE") {
+                        depth += 1;
+                    } else if tokens.is_keyword(i, "END") {
+                        depth -= 1;
+                    }
+                    i += 1;
+                }
+            }
+        }
+        if tokens.is_punct(i, ';') {
+            if start < i {
+                ranges.push(start..i);
+            }
+            start = i + 1;
+        }
+        i += 1;
+    }
+    if start < tokens.len() {
+        ranges.push(start..tokens.len());
+    }
+    ranges
+}
+
+fn object(tokens: &Tokens, range: Range<usize>) -> Result<Object, Error> {
+    let mut p = Parser::new(tokens, range);
+    p.expect_keyword("CREATE")?;
+    let temporary = p.keyword("TEMP") || p.keyword("TEMPORARY");
+    if p.keyword("TABLE") {
+        if temporary {
+            return Err(p.unsupported());
+        }
+        table(p).map(Object::Table)
+    } else if p.keyword("VIEW") {
+        view(p, temporary).map(Object::View)
+    } else if p.keyword("TRIGGER") {
+        trigger(p, temporary).map(Object::Trigger)
+    } else if p.keywords(&["UNIQUE", "INDEX"]) {
+        index(p, true)
+    } else if p.keyword("INDEX") {
+        index(p, false)
+    } else if p.keyword("VIRTUAL") {
+        Err(p.unsupported())
+    } else {
+        Err(p.error())
+    }
+}
+
+/// Position in the tokens of a statement.
+struct Parser<'t, 'a> {
+    tokens: &'t Tokens<'a>,
+    statement: Range<usize>,
+    at: usize,
+    end: usize
+}
+
+impl<'t, 'a> Parser<'t, 'a> {
+    fn new(tokens: &'t Tokens<'a>, statement: Range<usize>) -> Self {
+        Self { tokens, at: statement.start, end: statement.end, statement }
+    }
+
+    /// Parser of a part of the statement.
+    fn part(&self, range: Range<usize>) -> Self {
+        Self { tokens: self.tokens, statement: self.statement.clone(), at: range.start, end: range.end }
+    }
+
+    fn at_end(&self) -> bool {
+        self.at >= self.end
+    }
+
+    fn peek(&self, keyword: &str) -> bool {
+        self.at < self.end && self.tokens.is_keyword(self.at, keyword)
+    }
+
+    /// Skips the keyword when it is next.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek(keyword);
+        if found {
+            self.at += 1;
+        }
+        found
+    }
+
+    /// Skips the keywords when they are next.
+    fn keywords(&mut self, keywords: &[&str]) -> bool {
+        let found = self.at + keywords.len() <= self.end && self.tokens.are_keywords(self.at, keywords);
+        if found {
+            self.at += keywords.len();
+        }
+        found
+    }
+
+    fn punct(&mut self, c: char) -> bool {
+        let found = self.at < self.end && self.tokens.is_punct(self.at, c);
+        if found {
+            self.at += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        if self.keyword(keyword) { Ok(()) } else { Err(self.error()) }
+    }
+
+    fn name(&mut self) -> Result<String, Error> {
+        let name = match self.tokens.kind(self.at) {
+            Some(Kind::Word | Kind::Quoted | Kind::String) if self.at < self.end => self.tokens.name(self.at),
+            _ => None
+        };
+        match name {
+            Some(name) => {
+                self.at += 1;
+                Ok(name)
+            },
+            None => Err(self.error())
+        }
+    }
+
+    /// Name of a schema object, without the schema it may be qualified with.
+    fn object_name(&mut self) -> Result<String, Error> {
+        let name = self.name()?;
+        if self.punct('.') { self.name() } else { Ok(name) }
+    }
+
+    fn if_not_exists(&mut self) {
+        self.keywords(&["IF", "NOT", "EXISTS"]);
+    }
+
+    /// Tokens inside the parentheses that follow.
+    fn parenthesized(&mut self) -> Result<Range<usize>, Error> {
+        if !(self.at < self.end && self.tokens.is_punct(self.at, '(')) {
+            return Err(self.error());
+        }
+        let close = self.tokens.closing(self.at);
+        if close >= self.end {
+            return Err(self.error());
+        }
+        let inner = self.at + 1..close;
+        self.at = close + 1;
+        Ok(inner)
+    }
+
+    /// Comma separated parts in the parentheses that follow, there is at least one and none is empty.
+    fn list(&mut self) -> Result<Vec<Range<usize>>, Error> {
+        let inner = self.parenthesized()?;
+        let mut parts = Vec::new();
+        let mut start = inner.start;
+        loop {
+            let end = self.tokens.find(start..inner.end, |i| self.tokens.is_punct(i, ',')).unwrap_or(inner.end);
+            if start == end {
+                return Err(self.part(end..self.end).error());
+            }
+            parts.push(start..end);
+            if end == inner.end {
+                return Ok(parts);
+            }
+            start = end + 1;
+        }
+    }
+
+    /// Names in the parentheses that follow, the collations and sort orders of indexed columns are skipped.
+    fn names(&mut self) -> Result<Vec<String>, Error> {
+        self.list()?.into_iter().map(|r| self.part(r).name()).collect()
+    }
+
+    fn text(&self, range: Range<usize>) -> &'a str {
+        self.tokens.text(range)
+    }
+
+    fn rest(&mut self) -> &'a str {
+        let text = self.text(self.at..self.end);
+        self.at = self.end;
+        text
+    }
+
+    fn expect_end(&self) -> Result<(), Error> {
+        if self.at_end() { Ok(()) } else { Err(self.error()) }
+    }
+
+    fn error(&self) -> Error {
+        let near = if self.at < self.end { self.text(self.at..self.at + 1) } else { "end" };
+        Error::Syntax { near: near.to_owned(), statement: self.text(self.statement.clone()).to_owned() }
+    }
+
+    fn unsupported(&self) -> Error {
+        Error::Unsupported { statement: self.text(self.statement.clone()).to_owned() }
+    }
+
+    /// `CONSTRAINT name`, when it is next.
+    fn constraint_name(&mut self) -> Result<Option<String>, Error> {
+        if self.keyword("CONSTRAINT") { self.name().map(Some) } else { Ok(None) }
+    }
+
+    /// `ON CONFLICT resolution`, when it is next.
+    fn conflict(&mut self) -> Result<Option<ConflictResolution>, Error> {
+        if !self.keywords(&["ON", "CONFLICT"]) {
+            return Ok(None);
+        }
+        let resolution = [
+            ("ROLLBACK", ConflictResolution::Rollback),
+            ("ABORT", ConflictResolution::Abort),
+            ("FAIL", ConflictResolution::Fail),
+            ("IGNORE", ConflictResolution::Ignore),
+            ("REPLACE", ConflictResolution::Replace)
+        ].into_iter().find(|(k, _)| self.keyword(k));
+        resolution.map(|(_, r)| Some(r)).ok_or_else(|| self.error())
+    }
+
+    /// Foreign key clause, from `REFERENCES`.
+    fn references(&mut self) -> Result<References, Error> {
+        self.expect_keyword("REFERENCES")?;
+        let table = self.name()?;
+        let columns = if self.peek_punct('(') { self.names()? } else { Vec::new() };
+        let mut references = References::new(&table, &columns.iter().map(String::as_str).collect::<Vec<&str>>());
+        loop {
+            if self.keywords(&["ON", "DELETE"]) {
+                references = references.on_delete(self.action()?);
+            } else if self.keywords(&["ON", "UPDATE"]) {
+                references = references.on_update(self.action()?);
+            } else if self.keyword("MATCH") {
+                let name = self.name()?;
+                references = match name.to_ascii_uppercase().as_str() {
+                    "SIMPLE" => references.match_(ForeignKeyMatch::Simple),
+                    "PARTIAL" => references.match_(ForeignKeyMatch::Partial),
+                    "FULL" => references.match_(ForeignKeyMatch::Full),
+                    _ => return Err(self.unsupported())
+                };
+            } else if self.keywords(&["NOT", "DEFERRABLE"]) {
+                references = references.not_deferrable();
+                let _ = self.keywords(&["INITIALLY", "DEFERRED"]) || self.keywords(&["INITIALLY", "IMMEDIATE"]);
+            } else if self.keyword("DEFERRABLE") {
+                references = if self.keywords(&["INITIALLY", "DEFERRED"]) {
+                    references.initially_deferred()
+                } else if self.keywords(&["INITIALLY", "IMMEDIATE"]) {
+                    references.initially_immediate()
+                } else {
+                    references.deferrable()
+                };
+            } else {
+                return Ok(references);
+            }
+        }
+    }
+
+    fn action(&mut self) -> Result<ForeignKeyAction, Error> {
+        if self.keywords(&["SET", "NULL"]) {
+            Ok(ForeignKeyAction::SetNull)
+        } else if self.keywords(&["SET", "DEFAULT"]) {
+            Ok(ForeignKeyAction::SetDefault)
+        } else if self.keyword("CASCADE") {
+            Ok(ForeignKeyAction::Cascade)
+        } else if self.keyword("RESTRICT") {
+            Ok(ForeignKeyAction::Restrict)
+        } else if self.keywords(&["NO", "ACTION"]) {
+            Ok(ForeignKeyAction::NoAction)
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn peek_punct(&self, c: char) -> bool {
+        self.at < self.end && self.tokens.is_punct(self.at, c)
+    }
+}
+
+/// Keywords starting a column constraint, they end the type of the column.
+const COLUMN_CONSTRAINTS: [&str; 11] = ["CONSTRAINT", "PRIMARY", "NOT", "NULL", "UNIQUE", "CHECK", "DEFAULT", "COLLATE", "REFERENCES",
+    "GENERATED", "AS"];
+
+/// `CREATE TABLE`, after `TABLE`.
+fn table(mut p: Parser) -> Result<Table, Error> {
+    p.if_not_exists();
+    let name = p.object_name()?;
+    if p.keyword("AS") {
+        return Err(p.unsupported());
+    }
+    let mut table = create_table(&name);
+    let mut constraints = Vec::new();
+    for part in p.list()? {
+        let is_constraint = ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"].iter().any(|k| p.tokens.is_keyword(part.start, k));
+        if is_constraint {
+            constraints.push(part);
+        } else {
+            column(&mut table, p.part(part))?;
+        }
+    }
+    for part in constraints {
+        table_constraint(&mut table, p.part(part))?;
+    }
+    while !p.at_end() {
+        if p.keyword("STRICT") {
+            table.strict();
+        } else if p.keywords(&["WITHOUT", "ROWID"]) {
+            table.without_rowid();
+        } else {
+            return Err(p.error());
+        }
+        if !p.punct(',') {
+            break;
+        }
+        if p.at_end() {
+            return Err(p.error());
+        }
+    }
+    p.expect_end()?;
+    Ok(table)
+}
+
+fn column(table: &mut Table, mut p: Parser) -> Result<(), Error> {
+    let name = p.name()?;
+    let start = p.at;
+    while !p.at_end() && matches!(p.tokens.kind(p.at), Some(Kind::Word | Kind::Quoted)) && !COLUMN_CONSTRAINTS.iter().any(|k| p.peek(k)) {
+        p.at += 1;
+    }
+    if p.at > start && p.peek_punct('(') {
+        p.parenthesized()?;
+    }
+    let typ = p.text(start..p.at);
+    let not_null = p.tokens.find(p.at..p.end, |i| p.tokens.are_keywords(i, &["NOT", "NULL"])).is_some();
+    let key = not_null || typ.eq_ignore_ascii_case("INTEGER");
+    let mut col = Column::new(&name).declared_type(typ).nullable();
+    let mut checks = Vec::new();
+    let mut check_name = None;
+    // Applies the name and conflict resolution of a constraint to the column when it is kept there.
+    let named = |col: Column, name: Option<String>, conflict: Option<ConflictResolution>| {
+        let col = match name {
+            Some(n) => col.name_constraint(&n),
+            None => col
+        };
+        match conflict {
+            Some(c) => col.on_conflict(c),
+            None => col
+        }
+    };
+    while !p.at_end() {
+        let constraint = p.constraint_name()?;
+        if p.keywords(&["PRIMARY", "KEY"]) {
+            let desc = if p.keyword("DESC") { Some(true) } else if p.keyword("ASC") { Some(false) } else { None };
+            let conflict = p.conflict()?;
+            let autoincrement = p.keyword("AUTOINCREMENT");
+            if key {
+                col = if autoincrement { col.autoincrement() } else { col.primary_key() };
+                col = match desc {
+                    Some(true) => col.desc(),
+                    Some(false) => col.asc(),
+                    None => col
+                };
+                col = named(col, constraint, conflict);
+            } else {
+                let mut pk = PrimaryKey::new(&[&name]);
+                if let Some(n) = constraint {
+                    pk = pk.name(&n);
+                }
+                if let Some(c) = conflict {
+                    pk = pk.on_conflict(c);
+                }
+                table.set_primary_key(pk);
+            }
+        } else if p.keywords(&["NOT", "NULL"]) {
+            let conflict = p.conflict()?;
+            col = named(col.not_null(), constraint, conflict);
+        } else if p.keyword("NULL") {
+            p.conflict()?;
+        } else if p.keyword("UNIQUE") {
+            let conflict = p.conflict()?;
+            if key {
+                col = named(col.unique(), constraint, conflict);
+            } else {
+                let mut unique = Unique::new(&[&name]);
+                if let Some(n) = constraint {
+                    unique = unique.name(&n);
+                }
+                if let Some(c) = conflict {
+                    unique = unique.on_conflict(c);
+                }
+                table.add_unique_constraint(unique);
+            }
+        } else if p.keyword("CHECK") {
+            let check = p.parenthesized()?;
+            checks.push(p.text(check));
+            check_name = check_name.or(constraint);
+        } else if p.keyword("DEFAULT") {
+            let start = p.at;
+            if p.peek_punct('(') {
+                p.parenthesized()?;
+            } else {
+                let _ = p.punct('-') || p.punct('+');
+                p.at += 1;
+            }
+            if p.at > p.end {
+                p.at = p.end;
+                return Err(p.error());
+            }
+            col = named(col.default_value(p.text(start..p.at)), constraint, None);
+        } else if p.keyword("COLLATE") {
+            let collation = p.name()?;
+            col = named(col.collate(&collation), constraint, None);
+        } else if p.peek("REFERENCES") {
+            let references = p.references()?;
+            col = named(col.references(references), constraint, None);
+        } else if p.keywords(&["GENERATED", "ALWAYS", "AS"]) || p.keyword("AS") {
+            let expr = p.parenthesized()?;
+            let expr = p.text(expr);
+            let typ = if p.keyword("STORED") {
+                GeneratedColumnType::Stored
+            } else {
+                p.keyword("VIRTUAL");
+                GeneratedColumnType::Virtual
+            };
+            col = named(col.generated(expr, typ), constraint, None);
+        } else {
+            return Err(p.error());
+        }
+    }
+    let check = match checks.as_slice() {
+        [] => None,
+        [check] => Some(check.to_string()),
+        checks => Some(checks.iter().map(|c| format!("({})", c)).collect::<Vec<String>>().join(" AND "))
+    };
+    if let Some(check) = check {
+        col = named(col.check(check.as_str()), check_name, None);
+    }
+    table.add_column(col);
+    Ok(())
+}
+
+fn table_constraint(table: &mut Table, mut p: Parser) -> Result<(), Error> {
+    let constraint = p.constraint_name()?;
+    if p.keywords(&["PRIMARY", "KEY"]) {
+        let columns = p.names()?;
+        let mut pk = PrimaryKey::new(&columns.iter().map(String::as_str).collect::<Vec<&str>>());
+        if let Some(n) = constraint {
+            pk = pk.name(&n);
+        }
+        if let Some(c) = p.conflict()? {
+            pk = pk.on_conflict(c);
+        }
+        table.set_primary_key(pk);
+    } else if p.keyword("UNIQUE") {
+        let columns = p.names()?;
+        let mut unique = Unique::new(&columns.iter().map(String::as_str).collect::<Vec<&str>>());
+        if let Some(n) = constraint {
+            unique = unique.name(&n);
+        }
+        if let Some(c) = p.conflict()? {
+            unique = unique.on_conflict(c);
+        }
+        table.add_unique_constraint(unique);
+    } else if p.keyword("CHECK") {
+        let check = p.parenthesized()?;
+        table.add_check(Check::new(p.text(check)));
+    } else if p.keywords(&["FOREIGN", "KEY"]) {
+        let columns = p.names()?;
+        let mut fk = ForeignKey::with_columns(&columns.iter().map(String::as_str).collect::<Vec<&str>>()).with_references(p.references()?);
+        if let Some(n) = constraint {
+            fk = fk.name(&n);
+        }
+        table.add_foreign_key(fk);
+    } else {
+        return Err(p.error());
+    }
+    p.expect_end()
+}
+
+/// `CREATE VIEW`, after `VIEW`.
+fn view(mut p: Parser, temporary: bool) -> Result<ViewCreateStmt, Error> {
+    p.if_not_exists();
+    let mut view = create_view(&p.object_name()?);
+    if temporary {
+        view = view.temporary();
+    }
+    if p.peek_punct('(') {
+        let columns = p.names()?;
+        view = view.columns(&columns.iter().map(String::as_str).collect::<Vec<&str>>());
+    }
+    p.expect_keyword("AS")?;
+    if p.at_end() {
+        return Err(p.error());
+    }
+    Ok(view.as_(p.rest()))
+}
+
+/// `CREATE TRIGGER`, after `TRIGGER`.
+fn trigger(mut p: Parser, temporary: bool) -> Result<TriggerFull, Error> {
+    p.if_not_exists();
+    let mut trigger = create_trigger(&p.object_name()?);
+    if temporary {
+        trigger = trigger.temporary();
+    }
+    let trigger = if p.keyword("AFTER") {
+        trigger.after()
+    } else if p.keywords(&["INSTEAD", "OF"]) {
+        trigger.instead_of()
+    } else {
+        p.keyword("BEFORE");
+        trigger.before()
+    };
+    let trigger = if p.keyword("INSERT") {
+        trigger.insert()
+    } else if p.keyword("DELETE") {
+        trigger.delete()
+    } else if p.keyword("UPDATE") {
+        if p.peek("OF") {
+            return Err(p.unsupported());
+        }
+        trigger.update()
+    } else {
+        return Err(p.error());
+    };
+    p.expect_keyword("ON")?;
+    let mut trigger = trigger.on(&p.object_name()?);
+    p.keywords(&["FOR", "EACH", "ROW"]);
+    let begin = p.tokens.find(p.at..p.end, |i| p.tokens.is_keyword(i, "BEGIN")).ok_or_else(|| p.error())?;
+    if p.keyword("WHEN") {
+        if p.at == begin {
+            return Err(p.error());
+        }
+        trigger = trigger.when(p.text(p.at..begin));
+    }
+    p.at = begin + 1;
+    if p.end <= p.at || !p.tokens.is_keyword(p.end - 1, "END") {
+        p.at = p.end;
+        return Err(p.error());
+    }
+    let statements = p.tokens.split(p.at..p.end - 1, ';').into_iter().map(|r| p.text(r)).collect::<Vec<&str>>();
+    if statements.is_empty() {
+        return Err(p.error());
+    }
+    Ok(trigger.statements(&statements))
+}
+
+/// `CREATE INDEX`, after `INDEX`.
+fn index(mut p: Parser, unique: bool) -> Result<Object, Error> {
+    p.if_not_exists();
+    let mut index = create_index(&p.object_name()?);
+    if unique {
+        index = index.unique();
+    }
+    p.expect_keyword("ON")?;
+    let table = p.name()?;
+    for part in p.list()? {
+        let mut c = p.part(part.clone());
+        let end = p.tokens.find(part.clone(), |i| ["COLLATE", "ASC", "DESC"].iter().any(|k| p.tokens.is_keyword(i, k))).unwrap_or(part.end);
+        let mut column = match end - part.start {
+            1 if matches!(p.tokens.kind(part.start), Some(Kind::Word | Kind::Quoted)) => IndexedColumn::new(&c.name()?),
+            _ => IndexedColumn::expr(p.text(part.start..end))
+        };
+        c.at = end;
+        if c.keyword("COLLATE") {
+            column = column.collate(&c.name()?);
+        }
+        if c.keyword("ASC") {
+            column = column.asc();
+        } else if c.keyword("DESC") {
+            column = column.desc();
+        }
+        c.expect_end()?;
+        index = index.column(column);
+    }
+    if p.keyword("WHERE") {
+        if p.at_end() {
+            return Err(p.error());
+        }
+        index = index.where_(p.rest());
+    }
+    p.expect_end()?;
+    Ok(Object::Index { table, index })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, parse_table, Error};
+    use crate::Statement;
+
+    #[test]
+    fn table() {
+        let table = parse_table("CREATE TABLE IF NOT EXISTS main.\"group\" (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            email VARCHAR(255) NOT NULL ON CONFLICT REPLACE COLLATE NOCASE,
+            name TEXT CONSTRAINT uq_name UNIQUE DEFAULT 'it''s me' CHECK (name <> '') CHECK(length(name) < 100),
+            score DOUBLE PRECISION DEFAULT -1.5,
+            created TEXT DEFAULT (datetime('now')),
+            parent INT REFERENCES \"group\" (id) ON DELETE SET NULL DEFERRABLE INITIALLY DEFERRED,
+            lower_email AS (lower(email)) STORED,
+            code,
+            CONSTRAINT fk_code FOREIGN KEY (code, name) REFERENCES codes (code, name) ON UPDATE CASCADE,
+            CHECK (score >= -1.5)
+        ) STRICT").unwrap();
+        assert_eq!(table.to_string(), "CREATE TABLE IF NOT EXISTS \"group\" (\n\
+            id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,\n\
+            email VARCHAR(255) NOT NULL ON CONFLICT REPLACE COLLATE NOCASE,\n\
+            name TEXT CHECK((name <> '') AND (length(name) < 100)) DEFAULT 'it''s me',\n\
+            score DOUBLE PRECISION DEFAULT -1.5,\n\
+            created TEXT DEFAULT (datetime('now')),\n\
+            parent INT REFERENCES \"group\" (id) ON DELETE SET NULL DEFERRABLE INITIALLY DEFERRED,\n\
+            lower_email  AS (lower(email)) STORED,\n\
+            code ,\n\
+            CONSTRAINT uq_name UNIQUE (name),\n CHECK(score >= -1.5) ,\n\
+            CONSTRAINT fk_code FOREIGN KEY (code, name) REFERENCES codes (code, name) ON UPDATE CASCADE\n\
+            ) STRICT;");
+        assert_eq!(parse_table(&table.to_string()).unwrap().to_string(), table.to_string());
+
+        let table = parse_table("CREATE TABLE tags (post INTEGER, name TEXT PRIMARY KEY, UNIQUE (post, name COLLATE NOCASE)) WITHOUT ROWID").unwrap();
+        assert_eq!(table.to_string(), "CREATE TABLE IF NOT EXISTS tags (\npost INTEGER,\nname TEXT,\nPRIMARY KEY (name),\nUNIQUE (post, name)\n) WITHOUT ROWID;");
+    }
+
+    #[test]
+    fn lowercase() {
+        let table = parse_table("CREATE TABLE t (id integer primary key autoincrement)").unwrap();
+        assert_eq!(table.validate(), Ok(()));
+        assert_eq!(table.to_string(), "CREATE TABLE IF NOT EXISTS t (\nid integer PRIMARY KEY AUTOINCREMENT NOT NULL\n);");
+
+        let table = parse_table("create table s (id integer primary key, n int not null, r real, t text, b blob, a any) strict").unwrap();
+        assert_eq!(table.validate(), Ok(()));
+        assert_eq!(table.to_string(), "CREATE TABLE IF NOT EXISTS s (\nid integer PRIMARY KEY NOT NULL,\nn int NOT NULL,\nr real,\nt text,\nb blob,\na any\n) STRICT;");
+    }
+
+    #[test]
+    fn script() {
+        let db = parse("
+            CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT, author INTEGER);
+            CREATE INDEX idx_title ON posts (title COLLATE NOCASE DESC, (lower(title)), author) WHERE title IS NOT NULL;
+            CREATE TABLE sqlite_sequence(name,seq);
+            CREATE TEMP VIEW titles (id, title) AS SELECT id, title FROM posts WHERE title <> ';';
+            CREATE TRIGGER IF NOT EXISTS posts_check BEFORE INSERT ON posts FOR EACH ROW WHEN new.title IS NULL BEGIN
+                SELECT CASE WHEN new.id < 0 THEN RAISE(ABORT, 'negative') END;
+                INSERT INTO log VALUES (new.id);
+            END;
+            CREATE TABLE log (id INTEGER);
+            CREATE UNIQUE INDEX idx_log ON log (id)").unwrap();
+        assert_eq!(db.try_to_sql().unwrap(), "CREATE TABLE IF NOT EXISTS posts (\n\
+            id INTEGER PRIMARY KEY NOT NULL,\n\
+            title TEXT,\n\
+            author INTEGER\n\
+            );\n\
+            CREATE INDEX idx_title ON posts (title COLLATE NOCASE DESC, (lower(title)), author) WHERE title IS NOT NULL;\n\
+            CREATE TABLE IF NOT EXISTS log (\nid INTEGER\n);\n\
+            CREATE UNIQUE INDEX idx_log ON log (id);\n\
+            CREATE TEMP VIEW IF NOT EXISTS titles(id, title) AS SELECT id, title FROM posts WHERE title <> ';';\n\
+            CREATE TRIGGER IF NOT EXISTS posts_check BEFORE INSERT ON posts\n\
+            WHEN new.title IS NULL\n\
+            BEGIN\n\
+            SELECT CASE WHEN new.id < 0 THEN RAISE(ABORT, 'negative') END;\n\
+            INSERT INTO log VALUES (new.id);\n\
+            END;");
+        assert_eq!(parse(&db.to_string()).unwrap().to_string(), db.to_string());
+    }
+
+    #[test]
+    fn errors() {
+        let near = |sql: &str| match parse(sql) {
+            Err(Error::Syntax { near, .. }) => near,
+            _ => panic!("no syntax error: {}", sql)
+        };
+        assert_eq!(near("CREATE TABLE t (a INTEGER PRIMARY)"), "PRIMARY");
+        assert_eq!(near("CREATE TABLE t (a, b) ROWID"), "ROWID");
+        assert_eq!(near("CREATE VIEW v AS"), "end");
+        assert_eq!(near("CREATE TABLE t ()"), ")");
+        assert_eq!(near("CREATE TABLE t (a,, b)"), ",");
+        assert_eq!(near("CREATE TABLE t (a, PRIMARY KEY ())"), ")");
+        assert_eq!(near("CREATE TABLE t (a PRIMARY KEY) STRICT,"), "end");
+        assert_eq!(near("CREATE INDEX i ON t (a,)"), ")");
+        assert_eq!(near("INSERT INTO t VALUES (1)"), "INSERT");
+        assert!(matches!(parse_table("CREATE VIEW v AS SELECT 1"), Err(Error::Syntax { .. })));
+        assert!(matches!(parse_table("CREATE TABLE a (x); CREATE TABLE b (y)"), Err(Error::Syntax { ref near, .. }) if near == "CREATE"));
+
+        let unsupported = ["CREATE VIRTUAL TABLE f USING fts5(a)", "CREATE TABLE t AS SELECT 1", "CREATE TEMP TABLE t (a)",
+            "CREATE TRIGGER t_a AFTER UPDATE OF a ON t BEGIN SELECT 1; END"];
+        for sql in unsupported {
+            assert_eq!(parse(sql).err(), Some(Error::Unsupported { statement: sql.to_owned() }));
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::expr::{self, Expr};
+use crate::{Ident, Value, Placeholder};
+use crate::ident;
+
+/// Column of a table holding values of the Rust type `T`, usually a constant generated by `#[derive(Table)]`.
+///
+/// It renders as the plain column name, comparisons only accept operands of a compatible type:
+///
+/// ```compile_fail
+/// use yaqub::query::TypedColumn;
+///
+/// const AGE: TypedColumn<i64> = TypedColumn::new("users", "age");
+/// AGE.eq("forty");
+/// ```
+pub struct TypedColumn<T> {
+    table: &'static str,
+    name: &'static str,
+    typ: PhantomData<fn() -> T>
+}
+
+impl<T> TypedColumn<T> {
+    pub const fn new(table: &'static str, name: &'static str) -> Self {
+        Self { table, name, typ: PhantomData }
+    }
+
+    pub fn table(&self) -> &'static str {
+        self.table
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// `table.column`, for queries joining several tables.
+    pub fn qualified(self) -> Expr {
+        expr::qualified_col(self.table, self.name)
+    }
+
+    pub fn eq(self, rhs: impl Operand<T>) -> Expr {
+        Expr::from(self).eq(rhs.into_expr())
+    }
+
+    pub fn ne(self, rhs: impl Operand<T>) -> Expr {
+        Expr::from(self).ne(rhs.into_expr())
+    }
+
+    pub fn lt(self, rhs: impl Operand<T>) -> Expr {
+        Expr::from(self).lt(rhs.into_expr())
+    }
+
+    pub fn le(self, rhs: impl Operand<T>) -> Expr {
+        Expr::from(self).le(rhs.into_expr())
+    }
+
+    pub fn gt(self, rhs: impl Operand<T>) -> Expr {
+        Expr::from(self).gt(rhs.into_expr())
+    }
+
+    pub fn ge(self, rhs: impl Operand<T>) -> Expr {
+        Expr::from(self).ge(rhs.into_expr())
+    }
+
+    pub fn like(self, pattern: impl Operand<T>) -> Expr {
+        Expr::from(self).like(pattern.into_expr())
+    }
+
+    pub fn between(self, low: impl Operand<T>, high: impl Operand<T>) -> Expr {
+        Expr::from(self).between(low.into_expr(), high.into_expr())
+    }
+
+    pub fn in_list<I>(self, list: I) -> Expr where I: IntoIterator, I::Item: Operand<T> {
+        Expr::from(self).in_list(list.into_iter().map(Operand::into_expr))
+    }
+
+    pub fn is_null(self) -> Expr {
+        Expr::from(self).is_null()
+    }
+
+    pub fn is_not_null(self) -> Expr {
+        Expr::from(self).is_not_null()
+    }
+}
+
+impl<T> Clone for TypedColumn<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TypedColumn<T> {}
+
+impl<T> fmt::Debug for TypedColumn<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TypedColumn({}.{})", self.table, self.name)
+    }
+}
+
+impl<T> fmt::Display for TypedColumn<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", Ident::new(self.name))
+    }
+}
+
+impl<T> From<TypedColumn<T>> for Expr {
+    fn from(column: TypedColumn<T>) -> Self {
+        expr::unqualified_col(column.name)
+    }
+}
+
+/// Value which can be compared with or assigned to a column of type `T`.
+///
+/// Rust values are inlined as literals, [`Value`]s are bound and [`Expr`]s are accepted as they are.
+pub trait Operand<T> {
+    fn into_expr(self) -> Expr;
+}
+
+impl<T> Operand<T> for TypedColumn<T> {
+    fn into_expr(self) -> Expr {
+        self.into()
+    }
+}
+
+impl<T> Operand<Option<T>> for TypedColumn<T> {
+    fn into_expr(self) -> Expr {
+        self.into()
+    }
+}
+
+impl<T> Operand<T> for TypedColumn<Option<T>> {
+    fn into_expr(self) -> Expr {
+        self.into()
+    }
+}
+
+impl<T> Operand<T> for Expr {
+    fn into_expr(self) -> Expr {
+        self
+    }
+}
+
+impl<T> Operand<T> for Value {
+    fn into_expr(self) -> Expr {
+        self.into()
+    }
+}
+
+impl<T> Operand<T> for Placeholder {
+    fn into_expr(self) -> Expr {
+        self.into()
+    }
+}
+
+macro_rules! implement_operand {
+    ($($column: ty),+ => $values: tt) => {
+        $(implement_operand!(@column $column => $values);)+
+    };
+    (@column $column: ty => [$($value: ty),+]) => {
+        $(
+            impl Operand<$column> for $value {
+                fn into_expr(self) -> Expr {
+                    expr::lit(self)
+                }
+            }
+
+            impl Operand<Option<$column>> for $value {
+                fn into_expr(self) -> Expr {
+                    expr::lit(self)
+                }
+            }
+        )+
+    }
+}
+
+implement_operand!(i64, i32, i16, i8, u32, u16, u8 => [i64, i32, i16, i8, u32, u16, u8]);
+implement_operand!(f64, f32 => [f64, f32, i64, i32, u32]);
+implement_operand!(String => [String, &str]);
+implement_operand!(Vec<u8> => [Vec<u8>, &[u8]]);
+implement_operand!(bool => [bool]);
+
+/// Columns given to [`select`](super::select), `group_by`, `order_by`, [`insert`](super::insert) and
+/// [`replace`](super::replace): a string, a slice of strings, a [`TypedColumn`] or a tuple of those.
+pub trait Columns {
+    /// Column names, as given.
+    fn names(&self) -> Vec<&str>;
+
+    /// Columns as SQL, strings are raw SQL such as `count(*)` unless they are bare column names, which are
+    /// quoted when needed like typed columns.
+    fn to_sql(&self) -> Vec<String>;
+}
+
+impl Columns for &str {
+    fn names(&self) -> Vec<&str> {
+        vec![self]
+    }
+
+    fn to_sql(&self) -> Vec<String> {
+        vec![ident::column_sql(self)]
+    }
+}
+
+impl Columns for &[&str] {
+    fn names(&self) -> Vec<&str> {
+        self.to_vec()
+    }
+
+    fn to_sql(&self) -> Vec<String> {
+        self.iter().map(|it| ident::column_sql(it)).collect()
+    }
+}
+
+impl<const N: usize> Columns for &[&str; N] {
+    fn names(&self) -> Vec<&str> {
+        self.to_vec()
+    }
+
+    fn to_sql(&self) -> Vec<String> {
+        self.iter().map(|it| ident::column_sql(it)).collect()
+    }
+}
+
+impl Columns for &Vec<&str> {
+    fn names(&self) -> Vec<&str> {
+        self.to_vec()
+    }
+
+    fn to_sql(&self) -> Vec<String> {
+        self.iter().map(|it| ident::column_sql(it)).collect()
+    }
+}
+
+impl<T> Columns for TypedColumn<T> {
+    fn names(&self) -> Vec<&str> {
+        vec![self.name]
+    }
+
+    fn to_sql(&self) -> Vec<String> {
+        vec![self.to_string()]
+    }
+}
+
+macro_rules! implement_columns_for_tuple {
+    ($($t: ident),+) => {
+        impl<$($t),+> Columns for ($($t,)+) where $($t: Columns),+ {
+            #[allow(non_snake_case)]
+            fn names(&self) -> Vec<&str> {
+                let ($($t,)+) = self;
+                let mut names = Vec::new();
+                $(names.extend($t.names());)+
+                names
+            }
+
+            #[allow(non_snake_case)]
+            fn to_sql(&self) -> Vec<String> {
+                let ($($t,)+) = self;
+                let mut sql = Vec::new();
+                $(sql.extend($t.to_sql());)+
+                sql
+            }
+        }
+    }
+}
+
+implement_columns_for_tuple!(A);
+implement_columns_for_tuple!(A, B);
+implement_columns_for_tuple!(A, B, C);
+implement_columns_for_tuple!(A, B, C, D);
+implement_columns_for_tuple!(A, B, C, D, E);
+implement_columns_for_tuple!(A, B, C, D, E, F);
+implement_columns_for_tuple!(A, B, C, D, E, F, G);
+implement_columns_for_tuple!(A, B, C, D, E, F, G, H);
+implement_columns_for_tuple!(A, B, C, D, E, F, G, H, I);
+implement_columns_for_tuple!(A, B, C, D, E, F, G, H, I, J);
+implement_columns_for_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+implement_columns_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+#[cfg(test)]
+mod tests {
+    use super::TypedColumn;
+    use crate::expr::value;
+    use crate::query::{select, insert, update};
+    use crate::Statement;
+
+    mod users {
+        use super::TypedColumn;
+
+        pub const ID: TypedColumn<i64> = TypedColumn::new("users", "id");
+        pub const EMAIL: TypedColumn<String> = TypedColumn::new("users", "email");
+        pub const NICK: TypedColumn<Option<String>> = TypedColumn::new("users", "nick");
+        pub const ORDER: TypedColumn<i32> = TypedColumn::new("users", "order");
+        pub const RATE: TypedColumn<f64> = TypedColumn::new("users", "rate.eur");
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(users::ID.eq(5).to_string(), "id = 5");
+        assert_eq!(users::EMAIL.eq("a'b").to_string(), "email = 'a''b'");
+        assert_eq!(users::NICK.eq(users::EMAIL).to_string(), "nick = email");
+        assert_eq!(users::ID.in_list([1, 2]).to_string(), "id IN (1, 2)");
+        assert_eq!(users::ID.qualified().eq(value(3)).to_string(), "users.id = ?");
+        assert_eq!(users::RATE.gt(1.5).to_string(), "\"rate.eur\" > 1.5");
+        assert_eq!(users::RATE.qualified().gt(1.5).to_string(), "users.\"rate.eur\" > 1.5");
+    }
+
+    #[test]
+    fn queries() {
+        let query = select((users::ID, users::EMAIL, "count(*)"))
+            .from(users::ID.table())
+            .where_(users::NICK.is_not_null())
+            .group_by(users::EMAIL)
+            .order_by((users::ORDER, users::ID))
+            .desc();
+        assert_eq!(query.to_string(), "SELECT id, email, count(*) FROM users WHERE nick IS NOT NULL GROUP BY email ORDER BY \"order\" ASC, id DESC");
+
+        let sql = insert((users::EMAIL, users::NICK)).into("users").values(&[value("a"), value("b")]).to_string();
+        assert_eq!(sql, "INSERT INTO users(email, nick) VALUES(?, ?)");
+
+        let query = update("users").set_column(users::EMAIL, "x@y").set_column(users::NICK, "x").where_(users::ID.eq(1));
+        assert_eq!(query.to_string(), "UPDATE users SET email = 'x@y', nick = 'x' WHERE id = 1");
+        assert!(query.params().is_empty());
+
+        let query = select(users::RATE).from("users").order_by(users::RATE);
+        assert_eq!(query.to_string(), "SELECT \"rate.eur\" FROM users ORDER BY \"rate.eur\" ASC");
+    }
+}
@@ -1,18 +1,48 @@
 use std::fmt;
 
+use crate::expr::{self, Expr};
+use crate::{Statement, Value, Error};
+use crate::ident::TableName;
+use super::returning::Returning;
+use super::with::WithClause;
+
 #[derive(Default)]
 struct RawDelete {
-    table: String,
-    where_: String
+    with: Option<WithClause>,
+    table: TableName,
+    where_: Option<Expr>,
+    returning: Returning
+}
+
+impl RawDelete {
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(ref with) = self.with {
+            with.validate()?;
+        }
+        Ok(())
+    }
+
+    fn collect_params(&self, params: &mut Vec<Value>) {
+        if let Some(ref with) = self.with {
+            with.collect_params(params);
+        }
+        if let Some(ref where_) = self.where_ {
+            where_.collect_params(params);
+        }
+        self.returning.collect_params(params);
+    }
 }
 
 impl fmt::Display for RawDelete {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref with) = self.with {
+            write!(f, "{}", with)?;
+        }
         write!(f, "DELETE FROM {}", self.table)?;
-        if !self.where_.is_empty() {
-            write!(f," WHERE {}", self.where_)?;
+        if let Some(ref where_) = self.where_ {
+            write!(f," WHERE {}", where_)?;
         }
-        Ok(())
+        write!(f, "{}", self.returning)
     }
 }
 
@@ -20,10 +50,20 @@ pub struct DeleteFrom(RawDelete);
 pub struct DeleteWhere(RawDelete);
 
 impl DeleteFrom {
-    pub fn where_(mut self, cond: &str) -> DeleteWhere {
-        self.0.where_ = cond.to_string();
+    pub(super) fn with_clause(mut self, with: WithClause) -> Self {
+        self.0.with = Some(with);
+        self
+    }
+
+    pub fn where_(mut self, cond: impl Into<Expr>) -> DeleteWhere {
+        self.0.where_ = Some(cond.into());
         DeleteWhere(self.0)
     }
+
+    pub fn returning<E>(mut self, columns: &[E]) -> Self where E: Into<Expr> + Clone {
+        self.0.returning.set(columns);
+        self
+    }
 }
 
 impl fmt::Display for DeleteFrom {
@@ -32,14 +72,31 @@ impl fmt::Display for DeleteFrom {
     }
 }
 
+impl Statement for DeleteFrom {
+    fn params(&self) -> Vec<Value> {
+        let mut params = Vec::new();
+        self.0.collect_params(&mut params);
+        params
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.0.validate()
+    }
+}
+
 impl DeleteWhere {
-    pub fn and(mut self, cond: &str) -> Self {
-        self.0.where_.push_str(&format!(" AND {}", cond));
+    pub fn and(mut self, cond: impl Into<Expr>) -> Self {
+        expr::push_and(&mut self.0.where_, cond.into());
+        self
+    }
+
+    pub fn or(mut self, cond: impl Into<Expr>) -> Self {
+        expr::push_or(&mut self.0.where_, cond.into());
         self
     }
 
-    pub fn or(mut self, cond: &str) -> Self {
-        self.0.where_.push_str(&format!(" OR {}", cond));
+    pub fn returning<E>(mut self, columns: &[E]) -> Self where E: Into<Expr> + Clone {
+        self.0.returning.set(columns);
         self
     }
 }
@@ -50,9 +107,21 @@ impl fmt::Display for DeleteWhere {
     }
 }
 
+impl Statement for DeleteWhere {
+    fn params(&self) -> Vec<Value> {
+        let mut params = Vec::new();
+        self.0.collect_params(&mut params);
+        params
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.0.validate()
+    }
+}
+
 pub fn delete_from(table: &str) -> DeleteFrom {
     let delete = RawDelete{
-        table: table.to_string(),
+        table: TableName::new(table),
         ..Default::default()
     };
 
@@ -69,6 +138,14 @@ mod test {
         assert_eq!(sql, "DELETE FROM users");
     }
 
+    #[test]
+    fn delete_quoted() {
+        let sql = delete_from("order").to_string();
+        assert_eq!(sql, "DELETE FROM \"order\"");
+        let sql = delete_from("main.users").to_string();
+        assert_eq!(sql, "DELETE FROM main.users");
+    }
+
     #[test]
     fn delete_where() {
         let sql = delete_from("users").
@@ -84,7 +161,7 @@ mod test {
         and("age > 65").
         or("userId = 77").
         to_string();
-        assert_eq!(sql, "DELETE FROM users WHERE age < 16 AND age > 65 OR userId = 77");
+        assert_eq!(sql, "DELETE FROM users WHERE (age < 16) AND (age > 65) OR (userId = 77)");
     }
 
     #[test]
@@ -92,9 +169,48 @@ mod test {
         use crate::query::select;
 
         let sql = delete_from("comments").
-        where_(&format!("userId IN ({})", select(&["userId"]).from("users").where_("banned = TRUE"))).
+        where_(format!("userId IN ({})", select(&["userId"]).from("users").where_("banned = TRUE"))).
         to_string();
 
         assert_eq!(sql, "DELETE FROM comments WHERE userId IN (SELECT userId FROM users WHERE banned = TRUE)");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn delete_with_expr_subquery() {
+        use crate::query::select;
+        use crate::expr::col;
+
+        let sql = delete_from("comments").
+        where_(col("userId").in_query(select(&["userId"]).from("users").where_("banned = TRUE"))).
+        or(col("userId").is_null()).
+        to_string();
+
+        assert_eq!(sql, "DELETE FROM comments WHERE userId IN (SELECT userId FROM users WHERE banned = TRUE) OR userId IS NULL");
+    }
+
+    #[test]
+    fn delete_params() {
+        use crate::expr::{col, value};
+        use crate::{Statement, Value};
+
+        let (sql, params) = delete_from("users").
+        where_(col("age").lt(value(16))).
+        or(col("login").eq(value("pooh"))).
+        to_sql_with_params();
+        assert_eq!(sql, "DELETE FROM users WHERE age < ? OR login = ?");
+        assert_eq!(params, vec![Value::Integer(16), Value::Text("pooh".into())]);
+    }
+
+    #[test]
+    fn delete_returning() {
+        let sql = delete_from("users").returning(&["*"]).to_string();
+        assert_eq!(sql, "DELETE FROM users RETURNING *");
+
+        let sql = delete_from("users").
+        where_("age < 16").
+        or("age > 65").
+        returning(&["userId"]).
+        to_string();
+        assert_eq!(sql, "DELETE FROM users WHERE (age < 16) OR (age > 65) RETURNING userId");
+    }
+}
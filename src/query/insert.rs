@@ -1,53 +1,411 @@
 use std::fmt;
 
+use crate::expr::{self, Expr};
+use crate::{Statement, Value, Ident, ConflictResolution, Error};
+use crate::ident::{self, fmt_idents, TableName};
+use crate::query::Subquery;
+use super::returning::Returning;
+use super::with::WithClause;
+use super::column::Columns;
+
+#[derive(Debug, Clone)]
+enum InsertSource {
+    Values(Vec<Vec<Expr>>),
+    Select(Box<Subquery>),
+    DefaultValues
+}
+
+impl Default for InsertSource {
+    fn default() -> Self {
+        Self::Values(Vec::new())
+    }
+}
+
+impl InsertSource {
+    fn collect_params(&self, params: &mut Vec<Value>) {
+        match self {
+            Self::Values(rows) => rows.iter().flatten().for_each(|v| v.collect_params(params)),
+            Self::Select(query) => query.collect_params(params),
+            Self::DefaultValues => {}
+        }
+    }
+}
+
+impl fmt::Display for InsertSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Values(rows) => {
+                let rows = rows.iter()
+                    .map(|row| format!("({})", row.iter().map(|it| it.to_string()).collect::<Vec<String>>().join(", ")))
+                    .collect::<Vec<String>>();
+                write!(f, "VALUES{}", rows.join(", "))
+            },
+            Self::Select(query) => write!(f, "{}", query),
+            Self::DefaultValues => write!(f, "DEFAULT VALUES")
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum UpsertAction {
+    Nothing,
+    Update(Vec<(Ident, Expr)>, Option<Expr>)
+}
+
+/// `ON CONFLICT` clause of an insert, see <https://www.sqlite.org/lang_upsert.html>
+#[derive(Debug, Clone)]
+struct Upsert {
+    target: Vec<Ident>,
+    target_where: Option<Expr>,
+    action: UpsertAction
+}
+
+impl Upsert {
+    fn new(target: &[&str]) -> Self {
+        Self {
+            target: ident::idents(target),
+            target_where: None,
+            action: UpsertAction::Nothing
+        }
+    }
+
+    fn collect_params(&self, params: &mut Vec<Value>) {
+        if let Some(ref w) = self.target_where {
+            w.collect_params(params);
+        }
+        if let UpsertAction::Update(ref data, ref where_) = self.action {
+            data.iter().for_each(|it| it.1.collect_params(params));
+            if let Some(ref w) = where_ {
+                w.collect_params(params);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Upsert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ON CONFLICT")?;
+        if !self.target.is_empty() {
+            write!(f, "({})", fmt_idents(&self.target))?;
+            if let Some(ref w) = self.target_where {
+                write!(f, " WHERE {}", w)?;
+            }
+        }
+        match self.action {
+            UpsertAction::Nothing => write!(f, " DO NOTHING"),
+            UpsertAction::Update(ref data, ref where_) => {
+                let data = data.iter().map(|it| format!("{} = {}", it.0, it.1)).collect::<Vec<String>>().join(", ");
+                write!(f, " DO UPDATE SET {}", data)?;
+                if let Some(ref w) = where_ {
+                    write!(f, " WHERE {}", w)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 #[derive(Default)]
 struct RawInsert {
-    table: String,
-    columns: String,
-    values: String
+    with: Option<WithClause>,
+    table: TableName,
+    replace: bool,
+    or: Option<ConflictResolution>,
+    columns: Vec<Ident>,
+    source: InsertSource,
+    upserts: Vec<Upsert>,
+    returning: Returning
+}
+
+impl RawInsert {
+    fn collect_params(&self, params: &mut Vec<Value>) {
+        if let Some(ref with) = self.with {
+            with.collect_params(params);
+        }
+        self.source.collect_params(params);
+        self.upserts.iter().for_each(|u| u.collect_params(params));
+        self.returning.collect_params(params);
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(ref with) = self.with {
+            with.validate()?;
+        }
+        if self.replace && self.or.is_some() {
+            return Err(Error::ReplaceResolution { table: self.table.as_str().to_owned() });
+        }
+        if self.upserts.iter().rev().skip(1).any(|u| u.target.is_empty()) {
+            return Err(Error::UpsertTarget { table: self.table.as_str().to_owned() });
+        }
+        match self.source {
+            InsertSource::Values(ref rows) => {
+                if let Some(i) = rows.iter().position(|row| row.is_empty()) {
+                    return Err(Error::NoValues { table: self.table.as_str().to_owned(), row: i + 1 });
+                }
+                let expected = if self.columns.is_empty() { rows.first().map(|r| r.len()) } else { Some(self.columns.len()) };
+                if let Some(expected) = expected {
+                    if let Some((i, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != expected) {
+                        return Err(Error::ValueCount { table: self.table.as_str().to_owned(), row: i + 1, expected, found: row.len() });
+                    }
+                }
+                Ok(())
+            },
+            InsertSource::Select(ref query) => query.validate(),
+            InsertSource::DefaultValues if !self.columns.is_empty() =>
+                Err(Error::DefaultValuesColumns { table: self.table.as_str().to_owned() }),
+            InsertSource::DefaultValues => Ok(())
+        }
+    }
+
+    fn push_row(&mut self, row: Vec<Expr>) {
+        if let InsertSource::Values(ref mut rows) = self.source {
+            rows.push(row);
+        }
+    }
+
+    fn last_upsert(&mut self) -> &mut Upsert {
+        self.upserts.last_mut().expect("upsert clause has been added")
+    }
 }
 
 impl fmt::Display for RawInsert {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "INSERT INTO {}({}) VALUES({})", self.table, self.columns, self.values)
+        if let Some(ref with) = self.with {
+            write!(f, "{}", with)?;
+        }
+        if self.replace {
+            write!(f, "REPLACE")?;
+        } else {
+            write!(f, "INSERT")?;
+            if let Some(or) = self.or {
+                write!(f, " OR {}", or)?;
+            }
+        }
+        write!(f, " INTO {}", self.table)?;
+        if !self.columns.is_empty() {
+            write!(f, "({})", fmt_idents(&self.columns))?;
+        }
+        write!(f, " {}", self.source)?;
+        for u in &self.upserts {
+            write!(f, " {}", u)?;
+        }
+        write!(f, "{}", self.returning)
     }
 }
 
 pub struct Insert(RawInsert);
 pub struct InsertInto(RawInsert);
+/// Insert of rows given with `values`. Only these inserts take more rows, not the ones of `select` or `default_values`:
+///
+/// ```compile_fail
+/// use yaqub::query::insert;
+///
+/// insert(&["a"]).into("t").default_values().values(&["1"]);
+/// ```
+pub struct InsertValues(RawInsert);
+/// Insert of `DEFAULT VALUES`, which cannot have an upsert clause:
+///
+/// ```compile_fail
+/// use yaqub::query::insert;
+///
+/// insert(&[]).into("t").default_values().on_conflict(&[]).do_nothing();
+/// ```
+pub struct InsertDefaultValues(RawInsert);
 pub struct FinalInsert(RawInsert);
-
+pub struct OnConflict(RawInsert);
+pub struct UpsertUpdate(RawInsert);
 
 impl Insert {
+    pub(super) fn with_clause(mut self, with: WithClause) -> Self {
+        self.0.with = Some(with);
+        self
+    }
+
     pub fn into(mut self, table: &str) -> InsertInto {
-        self.0.table = table.to_string();
+        self.0.table = TableName::new(table);
         InsertInto(self.0)
     }
+
+    /// `INSERT OR <resolution>`
+    pub fn or(mut self, resolution: ConflictResolution) -> Self {
+        self.0.or = Some(resolution);
+        self
+    }
+
+    pub fn or_replace(self) -> Self {
+        self.or(ConflictResolution::Replace)
+    }
+
+    pub fn or_ignore(self) -> Self {
+        self.or(ConflictResolution::Ignore)
+    }
+
+    pub fn or_abort(self) -> Self {
+        self.or(ConflictResolution::Abort)
+    }
+
+    pub fn or_fail(self) -> Self {
+        self.or(ConflictResolution::Fail)
+    }
+
+    pub fn or_rollback(self) -> Self {
+        self.or(ConflictResolution::Rollback)
+    }
 }
 
 impl InsertInto {
-    pub fn values(mut self, values: &[&str]) -> FinalInsert {
-        self.0.values = values.join(", ");
+    /// Strings are inserted as raw SQL, [`Value`]s are bound to placeholders.
+    pub fn values<E>(mut self, values: &[E]) -> InsertValues where E: Into<Expr> + Clone {
+        self.0.push_row(values.iter().cloned().map(Into::into).collect());
+        InsertValues(self.0)
+    }
+
+    /// `INSERT INTO ... SELECT`. When an upsert clause follows, `WHERE true` is added to a select without
+    /// a `WHERE` clause, so that SQLite does not take `ON` for a join constraint.
+    pub fn select(mut self, query: impl Into<Subquery>) -> FinalInsert {
+        self.0.source = InsertSource::Select(Box::new(query.into()));
         FinalInsert(self.0)
     }
+
+    pub fn default_values(mut self) -> InsertDefaultValues {
+        self.0.source = InsertSource::DefaultValues;
+        InsertDefaultValues(self.0)
+    }
 }
 
-impl fmt::Display for FinalInsert {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+impl InsertValues {
+    /// Adds another row.
+    pub fn values<E>(mut self, values: &[E]) -> Self where E: Into<Expr> + Clone {
+        self.0.push_row(values.iter().cloned().map(Into::into).collect());
+        self
+    }
+
+    pub fn returning<E>(mut self, columns: &[E]) -> Self where E: Into<Expr> + Clone {
+        self.0.returning.set(columns);
+        self
+    }
+
+    pub fn on_conflict(self, target: &[&str]) -> OnConflict {
+        FinalInsert(self.0).on_conflict(target)
+    }
+}
+
+impl InsertDefaultValues {
+    pub fn returning<E>(mut self, columns: &[E]) -> Self where E: Into<Expr> + Clone {
+        self.0.returning.set(columns);
+        self
+    }
+}
+
+impl FinalInsert {
+    /// Columns or expressions of the inserted rows returned by the statement.
+    pub fn returning<E>(mut self, columns: &[E]) -> Self where E: Into<Expr> + Clone {
+        self.0.returning.set(columns);
+        self
+    }
+
+    /// Starts an upsert clause, an empty target is only allowed in the last clause.
+    pub fn on_conflict(mut self, target: &[&str]) -> OnConflict {
+        if let InsertSource::Select(ref mut query) = self.0.source {
+            query.ensure_where();
+        }
+        self.0.upserts.push(Upsert::new(target));
+        OnConflict(self.0)
+    }
+}
+
+impl OnConflict {
+    /// Condition of the partial index used as the conflict target.
+    pub fn where_(mut self, cond: impl Into<Expr>) -> Self {
+        self.0.last_upsert().target_where = Some(cond.into());
+        self
+    }
+
+    pub fn do_nothing(mut self) -> FinalInsert {
+        self.0.last_upsert().action = UpsertAction::Nothing;
+        FinalInsert(self.0)
+    }
+
+    pub fn do_update<E>(mut self, data: &[(&str, E)]) -> UpsertUpdate where E: Into<Expr> + Clone {
+        let data = data.iter().map(|it| (Ident::new(it.0), it.1.clone().into())).collect();
+        self.0.last_upsert().action = UpsertAction::Update(data, None);
+        UpsertUpdate(self.0)
+    }
+
+    /// `DO UPDATE SET col = excluded.col` for every given column.
+    pub fn do_update_excluded(mut self, columns: &[&str]) -> UpsertUpdate {
+        let data = columns.iter().map(|c| (Ident::new(c), expr::col(&format!("excluded.{}", c)))).collect();
+        self.0.last_upsert().action = UpsertAction::Update(data, None);
+        UpsertUpdate(self.0)
+    }
+}
+
+impl UpsertUpdate {
+    pub fn where_(mut self, cond: impl Into<Expr>) -> FinalInsert {
+        if let UpsertAction::Update(_, ref mut where_) = self.0.last_upsert().action {
+            *where_ = Some(cond.into());
+        }
+        FinalInsert(self.0)
+    }
+
+    pub fn on_conflict(self, target: &[&str]) -> OnConflict {
+        FinalInsert(self.0).on_conflict(target)
+    }
+
+    pub fn returning<E>(self, columns: &[E]) -> FinalInsert where E: Into<Expr> + Clone {
+        FinalInsert(self.0).returning(columns)
+    }
+}
+
+macro_rules! implement_statement_for {
+    ($t: ty) => {
+        impl fmt::Display for $t {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl Statement for $t {
+            fn params(&self) -> Vec<Value> {
+                let mut params = Vec::new();
+                self.0.collect_params(&mut params);
+                params
+            }
+
+            fn validate(&self) -> Result<(), Error> {
+                self.0.validate()
+            }
+        }
     }
 }
 
-pub fn insert(columns: &[&str]) -> Insert {
+implement_statement_for!(InsertValues);
+implement_statement_for!(InsertDefaultValues);
+implement_statement_for!(FinalInsert);
+implement_statement_for!(UpsertUpdate);
+
+pub fn insert(columns: impl Columns) -> Insert {
+    Insert(RawInsert {
+        columns: ident::idents(&columns.names()),
+        ..Default::default()
+    })
+}
+
+/// `REPLACE INTO`, an alias of `INSERT OR REPLACE INTO`.
+pub fn replace(columns: impl Columns) -> Insert {
     Insert(RawInsert {
-        columns: columns.join(", "),
+        columns: ident::idents(&columns.names()),
+        replace: true,
         ..Default::default()
     })
 }
 
 #[cfg(test)]
 mod test {
-    use super::insert;
+    use super::{insert, replace};
+    use crate::query::select;
+    use crate::{ConflictResolution, Statement, Error};
+    use crate::expr::{col, value};
 
     #[test]
     fn insert_item() {
@@ -58,4 +416,196 @@ mod test {
         
         assert_eq!(sql, "INSERT INTO users(login, email, passwd) VALUES('Winnie the pooh', 'pooh@hundredacreforest.org', 'honey!!!')");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn insert_params() {
+        use crate::{Statement, Value};
+
+        let (sql, params) = insert(&["login", "email", "passwd"]).
+        into("users").
+        values(&[Value::from("Winnie the pooh"), Value::from("pooh@hundredacreforest.org"), Value::Null]).
+        to_sql_with_params();
+
+        assert_eq!(sql, "INSERT INTO users(login, email, passwd) VALUES(?, ?, ?)");
+        assert_eq!(params, vec![Value::Text("Winnie the pooh".into()), Value::Text("pooh@hundredacreforest.org".into()), Value::Null]);
+    }
+
+    #[test]
+    fn insert_quoted() {
+        let sql = insert(&["order", "group by"]).
+        into("values").
+        values(&["1", "2"]).
+        to_string();
+
+        assert_eq!(sql, "INSERT INTO \"values\"(\"order\", \"group by\") VALUES(1, 2)");
+    }
+
+    #[test]
+    fn insert_or() {
+        let sql = insert(&["login"]).or_replace().into("users").values(&["'pooh'"]).to_string();
+        assert_eq!(sql, "INSERT OR REPLACE INTO users(login) VALUES('pooh')");
+
+        let sql = insert(&["login"]).or_ignore().into("users").values(&["'pooh'"]).to_string();
+        assert_eq!(sql, "INSERT OR IGNORE INTO users(login) VALUES('pooh')");
+
+        let sql = insert(&["login"]).or_abort().into("users").values(&["'pooh'"]).to_string();
+        assert_eq!(sql, "INSERT OR ABORT INTO users(login) VALUES('pooh')");
+
+        let sql = insert(&["login"]).or_fail().into("users").values(&["'pooh'"]).to_string();
+        assert_eq!(sql, "INSERT OR FAIL INTO users(login) VALUES('pooh')");
+
+        let sql = insert(&["login"]).or_rollback().into("users").values(&["'pooh'"]).to_string();
+        assert_eq!(sql, "INSERT OR ROLLBACK INTO users(login) VALUES('pooh')");
+
+        let sql = insert(&["login"]).or(ConflictResolution::Abort).into("users").values(&["'pooh'"]).to_string();
+        assert_eq!(sql, "INSERT OR ABORT INTO users(login) VALUES('pooh')");
+    }
+
+    #[test]
+    fn replace_into() {
+        let sql = replace(&["login"]).into("users").values(&["'pooh'"]).to_string();
+        assert_eq!(sql, "REPLACE INTO users(login) VALUES('pooh')");
+
+        let query = replace(&["login"]).or_ignore().into("users").values(&["'pooh'"]);
+        assert_eq!(query.try_to_sql(), Err(Error::ReplaceResolution { table: "users".into() }));
+    }
+
+    #[test]
+    fn upsert_do_nothing() {
+        let sql = insert(&["login", "email"]).
+        into("users").
+        values(&["'pooh'", "'pooh@hundredacreforest.org'"]).
+        on_conflict(&["login"]).do_nothing().
+        to_string();
+
+        assert_eq!(sql, "INSERT INTO users(login, email) VALUES('pooh', 'pooh@hundredacreforest.org') ON CONFLICT(login) DO NOTHING");
+
+        let sql = insert(&["login"]).into("users").values(&["'pooh'"]).on_conflict(&[]).do_nothing().to_string();
+        assert_eq!(sql, "INSERT INTO users(login) VALUES('pooh') ON CONFLICT DO NOTHING");
+    }
+
+    #[test]
+    fn upsert_do_update() {
+        use crate::Statement;
+
+        let (sql, params) = insert(&["login", "email", "visits"]).
+        into("users").
+        values(&[value("pooh"), value("pooh@hundredacreforest.org"), value(1)]).
+        on_conflict(&["login"]).where_(col("active").eq(1)).
+        do_update(&[("visits", col("visits") + 1)]).
+        where_(col("excluded.email").ne(col("users.email"))).
+        to_sql_with_params();
+
+        assert_eq!(sql, "INSERT INTO users(login, email, visits) VALUES(?, ?, ?) ON CONFLICT(login) WHERE active = 1 DO UPDATE SET visits = visits + 1 WHERE excluded.email <> users.email");
+        assert_eq!(params.len(), 3);
+    }
+
+    #[test]
+    fn upsert_excluded_multi() {
+        let sql = insert(&["tenant", "login", "email"]).
+        into("users").
+        values(&["1", "'pooh'", "'pooh@hundredacreforest.org'"]).
+        on_conflict(&["tenant", "login"]).do_update_excluded(&["email"]).
+        on_conflict(&[]).do_nothing().
+        to_string();
+
+        assert_eq!(sql, "INSERT INTO users(tenant, login, email) VALUES(1, 'pooh', 'pooh@hundredacreforest.org') ON CONFLICT(tenant, login) DO UPDATE SET email = excluded.email ON CONFLICT DO NOTHING");
+
+        let query = insert(&["tenant", "login"]).
+        into("users").
+        values(&["1", "'pooh'"]).
+        on_conflict(&[]).do_nothing().
+        on_conflict(&["tenant", "login"]).do_update_excluded(&["login"]);
+        assert_eq!(query.try_to_sql(), Err(Error::UpsertTarget { table: "users".into() }));
+    }
+
+    #[test]
+    fn insert_multi_row() {
+        use crate::{Statement, Value};
+
+        let (sql, params) = insert(&["login", "email"]).
+        into("users").
+        values(&[value("pooh"), value("pooh@hundredacreforest.org")]).
+        values(&[value("piglet"), value("piglet@hundredacreforest.org")]).
+        values(&["'eeyore'", "NULL"]).
+        to_sql_with_params();
+
+        assert_eq!(sql, "INSERT INTO users(login, email) VALUES(?, ?), (?, ?), ('eeyore', NULL)");
+        assert_eq!(params, vec![Value::from("pooh"), Value::from("pooh@hundredacreforest.org"), Value::from("piglet"), Value::from("piglet@hundredacreforest.org")]);
+    }
+
+    #[test]
+    fn insert_without_columns() {
+        let sql = insert(&[]).into("points").values(&["1", "2"]).values(&["3", "4"]).to_string();
+        assert_eq!(sql, "INSERT INTO points VALUES(1, 2), (3, 4)");
+    }
+
+    #[test]
+    fn insert_column_count_mismatch() {
+        let query = insert(&["login", "email", "passwd"]).into("users").values(&["'pooh'", "'pooh@hundredacreforest.org'"]);
+        let err = query.try_to_sql().unwrap_err();
+        assert_eq!(err, Error::ValueCount { table: "users".into(), row: 1, expected: 3, found: 2 });
+        assert_eq!(err.to_string(), "row 1 inserted into users has 2 values, expected 3");
+    }
+
+    #[test]
+    fn insert_row_count_mismatch() {
+        let query = insert(&[]).into("points").values(&["1", "2"]).values(&["3", "4"]).values(&["5"]);
+        assert_eq!(query.build(), Err(Error::ValueCount { table: "points".into(), row: 3, expected: 2, found: 1 }));
+    }
+
+    #[test]
+    fn insert_empty_row() {
+        let query = insert(&[]).into("points").values::<&str>(&[]);
+        assert_eq!(query.try_to_sql(), Err(Error::NoValues { table: "points".into(), row: 1 }));
+    }
+
+    #[test]
+    fn insert_valid() {
+        let query = insert(&["x", "y"]).into("points").values(&["1", "2"]).values(&["3", "4"]);
+        assert_eq!(query.try_to_sql(), Ok("INSERT INTO points(x, y) VALUES(1, 2), (3, 4)".to_string()));
+    }
+
+    #[test]
+    fn insert_select() {
+        let sql = insert(&["login", "email"]).
+        into("archived_users").
+        select(select(&["login", "email"]).from("users").where_(col("active").eq(0))).
+        to_string();
+
+        assert_eq!(sql, "INSERT INTO archived_users(login, email) SELECT login, email FROM users WHERE active = 0");
+
+        let sql = insert(&["login", "email"]).
+        into("archived_users").
+        select(select(&["login", "email"]).from("users")).
+        on_conflict(&["login"]).do_nothing().
+        to_string();
+        assert_eq!(sql, "INSERT INTO archived_users(login, email) SELECT login, email FROM users WHERE true ON CONFLICT(login) DO NOTHING");
+    }
+
+    #[test]
+    fn insert_default_values() {
+        let sql = insert(&[]).into("counters").default_values().to_string();
+        assert_eq!(sql, "INSERT INTO counters DEFAULT VALUES");
+
+        let sql = insert(&[]).into("counters").default_values().returning(&["id"]).to_string();
+        assert_eq!(sql, "INSERT INTO counters DEFAULT VALUES RETURNING id");
+
+        let query = insert(&["n"]).into("counters").default_values();
+        assert_eq!(query.try_to_sql(), Err(Error::DefaultValuesColumns { table: "counters".into() }));
+    }
+
+    #[test]
+    fn insert_returning() {
+        let sql = insert(&["login"]).into("users").values(&["'pooh'"]).returning(&["userId", "created AS createdAt"]).to_string();
+        assert_eq!(sql, "INSERT INTO users(login) VALUES('pooh') RETURNING userId, created AS createdAt");
+
+        let sql = insert(&["login"]).
+        into("users").
+        values(&["'pooh'"]).
+        on_conflict(&["login"]).do_update(&[("visits", col("visits") + 1)]).
+        returning(&[col("visits")]).
+        to_string();
+        assert_eq!(sql, "INSERT INTO users(login) VALUES('pooh') ON CONFLICT(login) DO UPDATE SET visits = visits + 1 RETURNING visits");
+    }
+}
@@ -2,8 +2,15 @@ mod select;
 mod update;
 mod insert;
 mod delete;
+mod returning;
+mod with;
+mod window;
+mod column;
 
 pub use select::*;
 pub use update::*;
 pub use insert::*;
-pub use delete::*;
\ No newline at end of file
+pub use delete::*;
+pub use with::*;
+pub use window::*;
+pub use column::*;
\ No newline at end of file
@@ -0,0 +1,28 @@
+use std::fmt;
+
+use crate::expr::Expr;
+use crate::Value;
+
+/// `RETURNING` clause of INSERT, UPDATE and DELETE, rendered with a leading space when not empty.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Returning(Vec<Expr>);
+
+impl Returning {
+    pub(crate) fn set<E>(&mut self, columns: &[E]) where E: Into<Expr> + Clone {
+        self.0 = columns.iter().cloned().map(Into::into).collect();
+    }
+
+    pub(crate) fn collect_params(&self, params: &mut Vec<Value>) {
+        self.0.iter().for_each(|it| it.collect_params(params));
+    }
+}
+
+impl fmt::Display for Returning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.0.is_empty() {
+            let columns = self.0.iter().map(|it| it.to_string()).collect::<Vec<String>>().join(", ");
+            write!(f, " RETURNING {}", columns)?;
+        }
+        Ok(())
+    }
+}
@@ -1,41 +1,117 @@
 
 use std::fmt;
 
+use crate::expr::{self, Expr};
+use crate::{Statement, Value, Placeholder, Ident, Error};
+use crate::ident::{self, fmt_idents, TableName};
+use crate::order::SortOrder;
+use super::with::WithClause;
+use super::window::{Window, NamedWindow};
+use super::column::Columns;
+
+#[derive(Debug, Clone)]
+struct SortBy(String, SortOrder);
+
+impl fmt::Display for SortBy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.0, self.1)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TableRef {
+    name: TableName,
+    alias: Option<Ident>
+}
+
+impl TableRef {
+    fn new(name: &str) -> Self {
+        Self { name: TableName::new(name), alias: None }
+    }
+}
+
+impl fmt::Display for TableRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(ref a) = self.alias {
+            write!(f, " AS {}", a)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
-enum SortOrder {
-    Asc,
-    Desc
+enum JoinType {
+    Inner,
+    Left,
+    Cross,
+    Natural
 }
 
-impl fmt::Display for SortOrder {
+impl fmt::Display for JoinType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            Self::Asc => write!(f, "ASC"),
-            Self::Desc => write!(f, "DESC")
+            Self::Inner => write!(f, "INNER JOIN"),
+            Self::Left => write!(f, "LEFT JOIN"),
+            Self::Cross => write!(f, "CROSS JOIN"),
+            Self::Natural => write!(f, "NATURAL JOIN")
         }
     }
 }
 
 #[derive(Debug, Clone)]
-struct SortBy(String, SortOrder);
+enum JoinConstraint {
+    On(Expr),
+    Using(Vec<Ident>)
+}
 
-impl fmt::Display for SortBy {
+impl fmt::Display for JoinConstraint {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {}", self.0, self.1)
+        match self {
+            Self::On(cond) => write!(f, "ON {}", cond),
+            Self::Using(cols) => write!(f, "USING ({})", fmt_idents(cols))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Join {
+    typ: JoinType,
+    table: TableRef,
+    constraint: Option<JoinConstraint>
+}
+
+impl Join {
+    fn new(typ: JoinType, table: &str) -> Self {
+        Self { typ, table: TableRef::new(table), constraint: None }
+    }
+}
+
+impl fmt::Display for Join {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.typ, self.table)?;
+        if let Some(ref c) = self.constraint {
+            write!(f, " {}", c)?;
+        }
+        Ok(())
     }
 }
 
 #[derive(Debug,Clone)]
 struct RawQuery {
+    with: Option<WithClause>,
     select: String,
-    from: String,
+    columns: Vec<(Expr, Option<Ident>)>,
+    from: TableRef,
+    joins: Vec<Join>,
     sort_by: Vec<SortBy>,    
     group_by: String,
-    having: String,
-    where_: String,
+    having: Option<Expr>,
+    windows: Vec<NamedWindow>,
+    where_: Option<Expr>,
     distinct: bool,
-    limit: Option<String>,
-    offset: Option<String>
+    limit: Option<Expr>,
+    offset: Option<Expr>
 }
 
 #[derive(Debug,Clone)]
@@ -56,35 +132,146 @@ pub struct HavingQuery(RawQuery);
 #[derive(Debug,Clone)]
 pub struct FinalQuery(RawQuery);
 
+#[derive(Debug,Clone)]
+pub struct JoinQuery(RawQuery);
+
 impl RawQuery {
     fn new() -> Self {
         Self {
+            with: None,
             select: String::new(),
-            from: String::new(),
+            columns: Vec::new(),
+            from: TableRef::new(""),
+            joins: Vec::new(),
             sort_by: Vec::new(),
             group_by: String::new(),
-            having: String::new(),
-            where_: String::new(),
+            having: None,
+            windows: Vec::new(),
+            where_: None,
             distinct: false,
             limit: None,
             offset: None
         }
-    }    
+    }
+
+    fn join(&mut self, typ: JoinType, table: &str) {
+        self.joins.push(Join::new(typ, table));
+    }
+
+    fn last_table(&mut self) -> &mut TableRef {
+        match self.joins.last_mut() {
+            Some(join) => &mut join.table,
+            None => &mut self.from
+        }
+    }
+
+    fn set_constraint(&mut self, constraint: JoinConstraint) {
+        if let Some(join) = self.joins.last_mut() {
+            join.constraint = Some(constraint);
+        }
+    }
+
+    fn result_columns(&self) -> Option<usize> {
+        let items = if self.select.is_empty() { Vec::new() } else { split_top_level(&self.select) };
+        let star = items.iter().any(|it| {
+            let it = it.trim();
+            it == "*" || it.ends_with(".*")
+        });
+        if star { None } else { Some(items.len() + self.columns.len()) }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        match self.with {
+            Some(ref with) => with.validate(),
+            None => Ok(())
+        }
+    }
+
+    fn collect_params(&self, params: &mut Vec<Value>) {
+        if let Some(ref with) = self.with {
+            with.collect_params(params);
+        }
+        self.columns.iter().for_each(|c| c.0.collect_params(params));
+        for join in &self.joins {
+            if let Some(JoinConstraint::On(ref cond)) = join.constraint {
+                cond.collect_params(params);
+            }
+        }
+        let clauses = [&self.where_, &self.having];
+        for e in clauses.into_iter().flatten() {
+            e.collect_params(params);
+        }
+        self.windows.iter().for_each(|w| w.1.collect_params(params));
+        let clauses = [&self.limit, &self.offset];
+        for e in clauses.into_iter().flatten() {
+            e.collect_params(params);
+        }
+    }
+}
+
+/// Number given to `limit` or `offset`, or an anonymous placeholder when there is none.
+fn number_or_placeholder(value: Option<u32>) -> Expr {
+    match value {
+        Some(v) => expr::lit(v),
+        None => Placeholder::Anonymous.into()
+    }
 }
 
 impl SelectQuery {
+    pub(super) fn with_clause(mut self, with: WithClause) -> Self {
+        self.0.with = Some(with);
+        self
+    }
+
     pub fn from(mut self, table: &str) -> Self {
-        self.0.from = table.into();
+        self.0.from = TableRef::new(table);
+        self
+    }
+
+    /// Adds a result column given as an expression, eg. a window function call, after the `select` fields.
+    pub fn column(mut self, expr: impl Into<Expr>) -> Self {
+        self.0.columns.push((expr.into(), None));
+        self
+    }
+
+    pub fn column_as(mut self, expr: impl Into<Expr>, alias: &str) -> Self {
+        self.0.columns.push((expr.into(), Some(Ident::new(alias))));
+        self
+    }
+
+    /// Sets an alias of the most recently added table, either the one given to `from` or the last joined one.
+    pub fn as_(mut self, alias: &str) -> Self {
+        self.0.last_table().alias = Some(Ident::new(alias));
+        self
+    }
+
+    pub fn inner_join(mut self, table: &str) -> JoinQuery {
+        self.0.join(JoinType::Inner, table);
+        JoinQuery(self.0)
+    }
+
+    pub fn left_join(mut self, table: &str) -> JoinQuery {
+        self.0.join(JoinType::Left, table);
+        JoinQuery(self.0)
+    }
+
+    pub fn cross_join(mut self, table: &str) -> Self {
+        self.0.join(JoinType::Cross, table);
         self
     }
 
-    pub fn order_by(mut self, field: &str) -> OrderByQuery {      
-        self.0.sort_by.push(SortBy(field.into(), SortOrder::Asc));
+    pub fn natural_join(mut self, table: &str) -> Self {
+        self.0.join(JoinType::Natural, table);
+        self
+    }
+
+    pub fn order_by(mut self, fields: impl Columns) -> OrderByQuery {      
+        self.0.sort_by.extend(fields.to_sql().into_iter().map(|it| SortBy(it, SortOrder::Asc)));
         OrderByQuery(self.0)        
     }
 
     pub fn limit(mut self, value: Option<u32>) -> Self {
-        self.0.limit = if let Some(v) = value { Some(v.to_string()) } else { Some("?".to_string()) };
+        self.0.limit = Some(number_or_placeholder(value));
         self
     }
 
@@ -94,39 +281,56 @@ impl SelectQuery {
     }
 
     pub fn offset(mut self, value: Option<u32>) -> Self {
-        self.0.offset = if let Some(v) = value { Some(v.to_string()) } else { Some("?".to_string()) };
+        self.0.offset = Some(number_or_placeholder(value));
         self
     }
 
-    pub fn where_(mut self, cond: &str) -> WhereQuery {
-        self.0.where_ = cond.into();
+    pub fn where_(mut self, cond: impl Into<Expr>) -> WhereQuery {
+        self.0.where_ = Some(cond.into());
         WhereQuery(self.0)
     }
 
-    pub fn group_by(mut self, fields: &[&str]) -> GroupQuery {
-        self.0.group_by = fields.join(", ");
+    pub fn group_by(mut self, fields: impl Columns) -> GroupQuery {
+        self.0.group_by = fields.to_sql().join(", ");
         GroupQuery(self.0)
     }
 }
 
+impl JoinQuery {
+    pub fn as_(mut self, alias: &str) -> Self {
+        self.0.last_table().alias = Some(Ident::new(alias));
+        self
+    }
+
+    pub fn on(mut self, cond: impl Into<Expr>) -> SelectQuery {
+        self.0.set_constraint(JoinConstraint::On(cond.into()));
+        SelectQuery(self.0)
+    }
+
+    pub fn using(mut self, columns: &[&str]) -> SelectQuery {
+        self.0.set_constraint(JoinConstraint::Using(ident::idents(columns)));
+        SelectQuery(self.0)
+    }
+}
+
 impl WhereQuery {
-    pub fn and(mut self, cond: &str) -> Self {
-        self.0.where_.push_str(&format!(" AND {}", cond));
+    pub fn and(mut self, cond: impl Into<Expr>) -> Self {
+        expr::push_and(&mut self.0.where_, cond.into());
         self
     }
 
-    pub fn or(mut self, cond: &str) -> Self {
-        self.0.where_.push_str(&format!(" OR {}", cond));
+    pub fn or(mut self, cond: impl Into<Expr>) -> Self {
+        expr::push_or(&mut self.0.where_, cond.into());
         self
     }
 
-    pub fn order_by(mut self, field: &str) -> OrderByQuery {
-        self.0.sort_by.push(SortBy(field.into(), SortOrder::Asc));
+    pub fn order_by(mut self, fields: impl Columns) -> OrderByQuery {
+        self.0.sort_by.extend(fields.to_sql().into_iter().map(|it| SortBy(it, SortOrder::Asc)));
         OrderByQuery(self.0)
     }
 
-    pub fn group_by(mut self, fields: &[&str]) -> GroupQuery {
-        self.0.group_by = fields.join(", ");
+    pub fn group_by(mut self, fields: impl Columns) -> GroupQuery {
+        self.0.group_by = fields.to_sql().join(", ");
         GroupQuery(self.0)
     }
 }
@@ -146,50 +350,54 @@ impl OrderByQuery {
         self
     }
 
-    pub fn order_by(mut self, field: &str) -> Self {
-        self.0.sort_by.push(SortBy(field.into(), SortOrder::Asc));        
+    pub fn order_by(mut self, fields: impl Columns) -> Self {
+        self.0.sort_by.extend(fields.to_sql().into_iter().map(|it| SortBy(it, SortOrder::Asc)));        
         self
     }    
 }
 
 impl GroupQuery {
-    pub fn order_by(mut self, field: &str) -> OrderByQuery {
-        self.0.sort_by.push(SortBy(field.into(), SortOrder::Asc));
+    pub fn order_by(mut self, fields: impl Columns) -> OrderByQuery {
+        self.0.sort_by.extend(fields.to_sql().into_iter().map(|it| SortBy(it, SortOrder::Asc)));
         OrderByQuery(self.0)
     }
 
-    pub fn having(mut self, cond: &str) -> HavingQuery {
-        self.0.having = cond.into();
+    pub fn having(mut self, cond: impl Into<Expr>) -> HavingQuery {
+        self.0.having = Some(cond.into());
         HavingQuery(self.0)
     }
 }
 
 impl HavingQuery {
-    pub fn and(mut self, cond: &str) -> Self {
-        self.0.having.push_str(&format!(" AND {}", cond));
+    pub fn and(mut self, cond: impl Into<Expr>) -> Self {
+        expr::push_and(&mut self.0.having, cond.into());
         self
     }
 
-    pub fn or(mut self, cond: &str) -> Self {
-        self.0.having.push_str(&format!(" OR {}", cond));
+    pub fn or(mut self, cond: impl Into<Expr>) -> Self {
+        expr::push_or(&mut self.0.having, cond.into());
         self
     }
 
-    pub fn order_by(mut self, field: &str) -> OrderByQuery {
-        self.0.sort_by.push(SortBy(field.into(), SortOrder::Asc));
+    pub fn order_by(mut self, fields: impl Columns) -> OrderByQuery {
+        self.0.sort_by.extend(fields.to_sql().into_iter().map(|it| SortBy(it, SortOrder::Asc)));
         OrderByQuery(self.0)
     }
 }
 
-pub fn select(fields: &[&str]) -> SelectQuery {
+pub fn select(fields: impl Columns) -> SelectQuery {
     let mut query = RawQuery::new();
-    query.select = fields.join(", ");
+    query.select = fields.to_sql().join(", ");
     SelectQuery(query)
 }
 
 impl fmt::Display for RawQuery {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {        
-        let mut sql = "SELECT".to_string();
+        let mut sql = match self.with {
+            Some(ref with) => with.to_string(),
+            None => String::new()
+        };
+        sql.push_str("SELECT");
 
         if self.distinct {
             sql.push_str(" DISTINCT");
@@ -197,13 +405,27 @@ impl fmt::Display for RawQuery {
         //select
         sql.push(' ');
         sql.push_str(&self.select);
+        for (i, (column, alias)) in self.columns.iter().enumerate() {
+            if i > 0 || !self.select.is_empty() {
+                sql.push_str(", ");
+            }
+            sql.push_str(&column.to_string());
+            if let Some(alias) = alias {
+                sql.push_str(" AS ");
+                sql.push_str(&alias.to_string());
+            }
+        }
         //from
         sql.push_str(" FROM ");
-        sql.push_str(&self.from);
+        sql.push_str(&self.from.to_string());
+        for join in &self.joins {
+            sql.push(' ');
+            sql.push_str(&join.to_string());
+        }
         //where
-        if !self.where_.is_empty() {
+        if let Some(ref where_) = self.where_ {
             sql.push_str(" WHERE ");
-            sql.push_str(&self.where_);
+            sql.push_str(&where_.to_string());
         }
         //group by
         if !self.group_by.is_empty() {
@@ -211,9 +433,14 @@ impl fmt::Display for RawQuery {
             sql.push_str(&self.group_by);
         }
         //having
-        if !self.having.is_empty() {
+        if let Some(ref having) = self.having {
             sql.push_str(" HAVING ");
-            sql.push_str(&self.having);
+            sql.push_str(&having.to_string());
+        }
+        //window
+        if !self.windows.is_empty() {
+            sql.push_str(" WINDOW ");
+            sql.push_str(&self.windows.iter().map(|it| it.to_string()).collect::<Vec<String>>().join(", "));
         }
         //order by
         if !self.sort_by.is_empty() {
@@ -223,12 +450,12 @@ impl fmt::Display for RawQuery {
         //limit
         if let Some(ref limit) = self.limit {
             sql.push_str(" LIMIT ");
-            sql.push_str(limit);
+            sql.push_str(&limit.to_string());
         }
         //offset
         if let Some(ref offset) = self.offset {
             sql.push_str(" OFFSET ");
-            sql.push_str(offset);
+            sql.push_str(&offset.to_string());
         }       
         write!(f, "{}", sql)
     }
@@ -241,6 +468,18 @@ macro_rules! implement_display_for {
                 self.0.fmt(f)
             }
         }
+
+        impl Statement for $t {
+            fn params(&self) -> Vec<Value> {
+                let mut params = Vec::new();
+                self.0.collect_params(&mut params);
+                params
+            }
+
+            fn validate(&self) -> Result<(), Error> {
+                self.0.validate()
+            }
+        }
     }
 }
 
@@ -250,11 +489,379 @@ implement_display_for!(FinalQuery);
 implement_display_for!(HavingQuery);
 implement_display_for!(GroupQuery);
 implement_display_for!(OrderByQuery);
+implement_display_for!(JoinQuery);
+implement_display_for!(Subquery);
+
+/// A finished select statement used inside another statement, eg. as an operand of `IN` or `EXISTS`.
+#[derive(Debug, Clone)]
+pub struct Subquery(SubqueryKind);
+
+#[derive(Debug, Clone)]
+enum SubqueryKind {
+    Simple(Box<RawQuery>),
+    Compound(RawCompound)
+}
+
+impl SubqueryKind {
+    fn validate(&self) -> Result<(), Error> {
+        match self {
+            Self::Simple(q) => q.validate(),
+            Self::Compound(q) => q.validate()
+        }
+    }
+
+    fn collect_params(&self, params: &mut Vec<Value>) {
+        match self {
+            Self::Simple(q) => q.collect_params(params),
+            Self::Compound(q) => q.collect_params(params)
+        }
+    }
+}
+
+impl fmt::Display for SubqueryKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Simple(q) => q.fmt(f),
+            Self::Compound(q) => q.fmt(f)
+        }
+    }
+}
+
+impl Subquery {
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        self.0.validate()
+    }
+
+    pub(crate) fn collect_params(&self, params: &mut Vec<Value>) {
+        self.0.collect_params(params);
+    }
+
+    /// Clause which is not allowed in an arm of a compound select, `WITH` is allowed in the first one.
+    fn arm_clause(&self, first: bool) -> Option<&'static str> {
+        let q = match self.0 {
+            SubqueryKind::Simple(ref q) => q,
+            SubqueryKind::Compound(_) => return Some("compound operator")
+        };
+        if q.with.is_some() && !first {
+            Some("WITH")
+        } else if !q.sort_by.is_empty() {
+            Some("ORDER BY")
+        } else if q.limit.is_some() {
+            Some("LIMIT")
+        } else if q.offset.is_some() {
+            Some("OFFSET")
+        } else {
+            None
+        }
+    }
+
+    /// Adds `WHERE true` to the select, or to the last arm of a compound select, when it has no `WHERE`
+    /// clause, so that SQLite does not take a following `ON` for a join constraint.
+    pub(crate) fn ensure_where(&mut self) {
+        match self.0 {
+            SubqueryKind::Simple(ref mut q) => {
+                if q.where_.is_none() {
+                    q.where_ = Some(expr::raw("true"));
+                }
+            },
+            SubqueryKind::Compound(ref mut q) => match q.arms.last_mut() {
+                Some((_, arm)) => arm.ensure_where(),
+                None => q.first.ensure_where()
+            }
+        }
+    }
+}
+
+macro_rules! implement_subquery_from {
+    ($t: ty, $kind: ident) => {
+        impl From<$t> for Subquery {
+            fn from(query: $t) -> Self {
+                Subquery(SubqueryKind::$kind(query.0.into()))
+            }
+        }
+    }
+}
+
+implement_subquery_from!(SelectQuery, Simple);
+implement_subquery_from!(WhereQuery, Simple);
+implement_subquery_from!(FinalQuery, Simple);
+implement_subquery_from!(HavingQuery, Simple);
+implement_subquery_from!(GroupQuery, Simple);
+implement_subquery_from!(OrderByQuery, Simple);
+implement_subquery_from!(CompoundQuery, Compound);
+implement_subquery_from!(CompoundOrderBy, Compound);
+
+#[derive(Debug, Clone, Copy)]
+enum CompoundOperator {
+    Union,
+    UnionAll,
+    Intersect,
+    Except
+}
+
+impl fmt::Display for CompoundOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Union => write!(f, "UNION"),
+            Self::UnionAll => write!(f, "UNION ALL"),
+            Self::Intersect => write!(f, "INTERSECT"),
+            Self::Except => write!(f, "EXCEPT")
+        }
+    }
+}
+
+/// Select statements combined with compound operators. ORDER BY, LIMIT and OFFSET apply to the whole result.
+#[derive(Debug, Clone)]
+struct RawCompound {
+    first: Box<Subquery>,
+    arms: Vec<(CompoundOperator, Subquery)>,
+    sort_by: Vec<SortBy>,
+    limit: Option<Expr>,
+    offset: Option<Expr>
+}
+
+impl RawCompound {
+    fn new(first: Subquery) -> Self {
+        Self {
+            first: Box::new(first),
+            arms: Vec::new(),
+            sort_by: Vec::new(),
+            limit: None,
+            offset: None
+        }
+    }
+
+    fn push(&mut self, op: CompoundOperator, arm: Subquery) {
+        self.arms.push((op, arm));
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.first.validate()?;
+        if let Some(clause) = self.first.arm_clause(true) {
+            return Err(Error::CompoundArmClause { arm: 0, clause: clause.to_owned() });
+        }
+        let expected = self.first.result_columns();
+        for (i, (_, arm)) in self.arms.iter().enumerate() {
+            arm.validate()?;
+            if let Some(clause) = arm.arm_clause(false) {
+                return Err(Error::CompoundArmClause { arm: i + 1, clause: clause.to_owned() });
+            }
+            if let (Some(expected), Some(found)) = (expected, arm.result_columns()) {
+                if expected != found {
+                    return Err(Error::ResultColumnCount { arm: i + 1, expected, found });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn collect_params(&self, params: &mut Vec<Value>) {
+        self.first.collect_params(params);
+        self.arms.iter().for_each(|it| it.1.collect_params(params));
+        let clauses = [&self.limit, &self.offset];
+        for e in clauses.into_iter().flatten() {
+            e.collect_params(params);
+        }
+    }
+}
+
+impl fmt::Display for RawCompound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.first)?;
+        for (op, arm) in &self.arms {
+            write!(f, " {} {}", op, arm)?;
+        }
+        if !self.sort_by.is_empty() {
+            write!(f, " ORDER BY {}", self.sort_by.iter().map(|it| it.to_string()).collect::<Vec<String>>().join(", "))?;
+        }
+        if let Some(ref limit) = self.limit {
+            write!(f, " LIMIT {}", limit)?;
+        }
+        if let Some(ref offset) = self.offset {
+            write!(f, " OFFSET {}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl Subquery {
+    /// Number of result columns, `None` when it cannot be told, eg. for `SELECT *`.
+    pub(crate) fn result_columns(&self) -> Option<usize> {
+        match self.0 {
+            SubqueryKind::Simple(ref q) => q.result_columns(),
+            SubqueryKind::Compound(ref q) => q.first.result_columns()
+        }
+    }
+}
+
+/// Splits a list of raw SQL expressions at the commas which are not nested in parentheses or quotes.
+pub(crate) fn split_top_level(list: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {},
+            (None, '\'') | (None, '"') | (None, '`') => quote = Some(c),
+            (None, '[') => quote = Some(']'),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                items.push(&list[start..i]);
+                start = i + 1;
+            },
+            _ => {}
+        }
+    }
+    items.push(&list[start..]);
+    items
+}
+
+/// Select statement that can be an arm of a compound select.
+pub trait CompoundArm: Into<Subquery> {}
+
+impl CompoundArm for SelectQuery {}
+impl CompoundArm for WhereQuery {}
+impl CompoundArm for GroupQuery {}
+impl CompoundArm for HavingQuery {}
+
+macro_rules! implement_compound_for {
+    ($t: ty) => {
+        impl $t {
+            fn compound(self, op: CompoundOperator, other: impl CompoundArm) -> CompoundQuery {
+                let mut compound = RawCompound::new(self.into());
+                compound.push(op, other.into());
+                CompoundQuery(compound)
+            }
+
+            pub fn union(self, other: impl CompoundArm) -> CompoundQuery {
+                self.compound(CompoundOperator::Union, other)
+            }
+
+            pub fn union_all(self, other: impl CompoundArm) -> CompoundQuery {
+                self.compound(CompoundOperator::UnionAll, other)
+            }
+
+            pub fn intersect(self, other: impl CompoundArm) -> CompoundQuery {
+                self.compound(CompoundOperator::Intersect, other)
+            }
+
+            pub fn except(self, other: impl CompoundArm) -> CompoundQuery {
+                self.compound(CompoundOperator::Except, other)
+            }
+        }
+    }
+}
+
+macro_rules! implement_window_for {
+    ($t: ty) => {
+        impl $t {
+            /// Defines a named window in the `WINDOW` clause.
+            pub fn window(mut self, name: &str, window: Window) -> Self {
+                self.0.windows.push(NamedWindow(Ident::new(name), window));
+                self
+            }
+        }
+    }
+}
+
+implement_window_for!(SelectQuery);
+implement_window_for!(WhereQuery);
+implement_window_for!(GroupQuery);
+implement_window_for!(HavingQuery);
+
+implement_compound_for!(SelectQuery);
+implement_compound_for!(WhereQuery);
+implement_compound_for!(GroupQuery);
+implement_compound_for!(HavingQuery);
+
+#[derive(Debug, Clone)]
+pub struct CompoundQuery(RawCompound);
+
+#[derive(Debug, Clone)]
+pub struct CompoundOrderBy(RawCompound);
+
+impl CompoundQuery {
+    pub fn union(mut self, other: impl CompoundArm) -> Self {
+        self.0.push(CompoundOperator::Union, other.into());
+        self
+    }
+
+    pub fn union_all(mut self, other: impl CompoundArm) -> Self {
+        self.0.push(CompoundOperator::UnionAll, other.into());
+        self
+    }
+
+    pub fn intersect(mut self, other: impl CompoundArm) -> Self {
+        self.0.push(CompoundOperator::Intersect, other.into());
+        self
+    }
+
+    pub fn except(mut self, other: impl CompoundArm) -> Self {
+        self.0.push(CompoundOperator::Except, other.into());
+        self
+    }
+
+    /// Sorts the whole compound result, `field` has to be a result column name, alias or index.
+    pub fn order_by(mut self, fields: impl Columns) -> CompoundOrderBy {
+        self.0.sort_by.extend(fields.to_sql().into_iter().map(|it| SortBy(it, SortOrder::Asc)));
+        CompoundOrderBy(self.0)
+    }
+
+    pub fn limit(mut self, value: Option<u32>) -> Self {
+        self.0.limit = Some(number_or_placeholder(value));
+        self
+    }
+
+    pub fn offset(mut self, value: Option<u32>) -> Self {
+        self.0.offset = Some(number_or_placeholder(value));
+        self
+    }
+}
+
+impl CompoundOrderBy {
+    pub fn asc(mut self) -> Self {
+        if let Some(value) = self.0.sort_by.last_mut() {
+            value.1 = SortOrder::Asc;
+        }
+        self
+    }
+
+    pub fn desc(mut self) -> Self {
+        if let Some(value) = self.0.sort_by.last_mut() {
+            value.1 = SortOrder::Desc;
+        }
+        self
+    }
+
+    pub fn order_by(mut self, fields: impl Columns) -> Self {
+        self.0.sort_by.extend(fields.to_sql().into_iter().map(|it| SortBy(it, SortOrder::Asc)));
+        self
+    }
+
+    pub fn limit(mut self, value: Option<u32>) -> Self {
+        self.0.limit = Some(number_or_placeholder(value));
+        self
+    }
+
+    pub fn offset(mut self, value: Option<u32>) -> Self {
+        self.0.offset = Some(number_or_placeholder(value));
+        self
+    }
+}
+
+implement_display_for!(CompoundQuery);
+implement_display_for!(CompoundOrderBy);
 
 #[cfg(test)]
 mod test {
 
     use super::select;
+    use crate::query::with;
+    use crate::expr::{col, lit, exists, value, param, param_at, named_param};
+    use crate::{Statement, Value, Error};
 
     #[test]
     fn simple_query() {
@@ -333,7 +940,7 @@ mod test {
         .or("lastname = 'Kowalski'")
         .order_by("lastname")        
         .to_string();
-        assert_eq!(sql, "SELECT firstname, lastname, age, gender FROM people WHERE age >= 16 AND age <= 65 OR lastname = 'Kowalski' ORDER BY lastname ASC");
+        assert_eq!(sql, "SELECT firstname, lastname, age, gender FROM people WHERE (age >= 16) AND (age <= 65) OR (lastname = 'Kowalski') ORDER BY lastname ASC");
     }
 
     #[test]
@@ -354,7 +961,7 @@ mod test {
         .having("count > 1").and("sum < 100").or("count = sum")
         .order_by("lastname")
         .to_string();
-        assert_eq!(sql, "SELECT firstname, lastname, Count(age) AS count, Sum(age) AS sum FROM people GROUP BY firstname, lastname HAVING count > 1 AND sum < 100 OR count = sum ORDER BY lastname ASC");
+        assert_eq!(sql, "SELECT firstname, lastname, Count(age) AS count, Sum(age) AS sum FROM people GROUP BY firstname, lastname HAVING (count > 1) AND (sum < 100) OR (count = sum) ORDER BY lastname ASC");
     }
 
     #[test]
@@ -366,7 +973,7 @@ mod test {
         .having("count > 1").and("sum < 100")
         .order_by("lastname")
         .to_string();
-        assert_eq!(sql, "SELECT firstname, lastname, Count(age) AS count, Sum(age) AS sum FROM people WHERE lastname <> 'Kowalski' AND firstname <> 'Jan' GROUP BY firstname, lastname HAVING count > 1 AND sum < 100 ORDER BY lastname ASC");
+        assert_eq!(sql, "SELECT firstname, lastname, Count(age) AS count, Sum(age) AS sum FROM people WHERE (lastname <> 'Kowalski') AND (firstname <> 'Jan') GROUP BY firstname, lastname HAVING (count > 1) AND (sum < 100) ORDER BY lastname ASC");
     }
 
     #[test]
@@ -381,17 +988,185 @@ mod test {
         .having("count > 1").and("sum < 100")
         .order_by("lastname")
         .to_string();
-        assert_eq!(sql, "SELECT DISTINCT firstname, lastname, Count(age) AS count, Sum(age) AS sum FROM people WHERE lastname <> 'Kowalski' AND firstname <> 'Jan' GROUP BY firstname, lastname HAVING count > 1 AND sum < 100 ORDER BY lastname ASC LIMIT ? OFFSET ?");
+        assert_eq!(sql, "SELECT DISTINCT firstname, lastname, Count(age) AS count, Sum(age) AS sum FROM people WHERE (lastname <> 'Kowalski') AND (firstname <> 'Jan') GROUP BY firstname, lastname HAVING (count > 1) AND (sum < 100) ORDER BY lastname ASC LIMIT ? OFFSET ?");
     }
 
     #[test]
     fn sub_query() {
         let sql = select(&["title"])
         .from("books")
-        .where_(&format!("authorId = ({})", select(&["authorId"]).from("authors").where_("name = 'Walter Jon Williams'")).to_string())
+        .where_(format!("authorId = ({})", select(&["authorId"]).from("authors").where_("name = 'Walter Jon Williams'")).to_string())
         .order_by("title")
         .to_string();
 
         assert_eq!(sql, "SELECT title FROM books WHERE authorId = (SELECT authorId FROM authors WHERE name = 'Walter Jon Williams') ORDER BY title ASC")
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn inner_join_query() {
+        let sql = select(&["b.title", "a.name"])
+        .from("books").as_("b")
+        .inner_join("authors").as_("a").on("a.authorId = b.authorId")
+        .where_("a.name = 'Walter Jon Williams'")
+        .to_string();
+        assert_eq!(sql, "SELECT b.title, a.name FROM books AS b INNER JOIN authors AS a ON a.authorId = b.authorId WHERE a.name = 'Walter Jon Williams'");
+    }
+
+    #[test]
+    fn left_join_using_query() {
+        let sql = select(&["title", "name"])
+        .from("books")
+        .left_join("authors").using(&["authorId"])
+        .left_join("publishers").using(&["publisherId", "country"])
+        .to_string();
+        assert_eq!(sql, "SELECT title, name FROM books LEFT JOIN authors USING (authorId) LEFT JOIN publishers USING (publisherId, country)");
+    }
+
+    #[test]
+    fn quoted_names_query() {
+        let sql = select(&["*"])
+        .from("order").as_("o")
+        .inner_join("order items").as_("group").using(&["order id"])
+        .where_(col("o.select").eq(1))
+        .to_string();
+        assert_eq!(sql, "SELECT * FROM \"order\" AS o INNER JOIN \"order items\" AS \"group\" USING (\"order id\") WHERE o.\"select\" = 1");
+
+        let sql = select(&["order", "o.group", "count(*)"]).from("main.orders").as_("o").group_by(&["o.group"]).order_by("order").to_string();
+        assert_eq!(sql, "SELECT \"order\", o.\"group\", count(*) FROM main.orders AS o GROUP BY o.\"group\" ORDER BY \"order\" ASC");
+    }
+
+    #[test]
+    fn cross_natural_join_query() {
+        let sql = select(&["*"])
+        .from("sizes")
+        .cross_join("colors").as_("c")
+        .natural_join("prices")
+        .order_by("c.name")
+        .to_string();
+        assert_eq!(sql, "SELECT * FROM sizes CROSS JOIN colors AS c NATURAL JOIN prices ORDER BY c.name ASC");
+    }
+
+    #[test]
+    fn where_expr_query() {
+        let sql = select(&["firstname", "lastname"])
+        .from("people")
+        .where_(col("age").ge(16))
+        .or(col("lastname").eq(lit("Kowalski")))
+        .and(col("age").le(65))
+        .to_string();
+        assert_eq!(sql, "SELECT firstname, lastname FROM people WHERE (age >= 16 OR lastname = 'Kowalski') AND age <= 65");
+    }
+
+    #[test]
+    fn expr_sub_query() {
+        let sql = select(&["name"])
+        .from("authors").as_("a")
+        .where_(exists(select(&["1"]).from("books").as_("b").where_(col("b.authorId").eq(col("a.authorId")))))
+        .to_string();
+        assert_eq!(sql, "SELECT name FROM authors AS a WHERE EXISTS (SELECT 1 FROM books AS b WHERE b.authorId = a.authorId)");
+    }
+
+    #[test]
+    fn params_query() {
+        let (sql, params) = select(&["title"])
+        .from("books")
+        .inner_join("authors").on(col("authors.authorId").eq(col("books.authorId")).and(col("authors.active").eq(value(true))))
+        .where_(col("title").like(value("%Rust%")))
+        .and(col("authorId").in_query(select(&["authorId"]).from("awards").where_(col("year").ge(value(2020)))))
+        .and(col("price").lt(value(49.5)))
+        .order_by("title")
+        .build()
+        .unwrap();
+        assert_eq!(sql, "SELECT title FROM books INNER JOIN authors ON authors.authorId = books.authorId AND authors.active = ? WHERE title LIKE ? AND authorId IN (SELECT authorId FROM awards WHERE year >= ?) AND price < ? ORDER BY title ASC");
+        assert_eq!(params, vec![Value::Integer(1), Value::Text("%Rust%".into()), Value::Integer(2020), Value::Real(49.5)]);
+    }
+
+    #[test]
+    fn mixed_placeholders() {
+        let query = select(&["a"]).from("t").where_(col("a").eq(param_at(1)).and(col("b").eq(value(5))));
+        assert_eq!(query.try_to_sql(), Err(Error::MixedPlaceholders));
+        let query = select(&["a"]).from("t").where_(col("a").eq(param()).and(col("b").eq(named_param("b"))));
+        assert_eq!(query.build(), Err(Error::MixedPlaceholders));
+        let query = select(&["a"]).from("t").where_(col("a").eq(param()).and(col("b").eq(value(5))));
+        assert_eq!(query.build(), Err(Error::MixedPlaceholders));
+
+        let query = select(&["a"]).from("t").where_(col("a").eq(param_at(1)).and(col("b").eq(named_param("b"))).and("c = '?' AND \"d?\" = 1"));
+        assert_eq!(query.try_to_sql(), Ok("SELECT a FROM t WHERE a = ?1 AND b = :b AND (c = '?' AND \"d?\" = 1)".to_string()));
+        assert!(select(&["a"]).from("t").where_(col("a").eq(param()).and(col("b").eq(param()))).build().is_ok());
+    }
+
+    #[test]
+    fn union_query() {
+        let sql = select(&["name", "email"])
+        .from("customers")
+        .union(select(&["name", "email"]).from("suppliers").where_(col("active").eq(1)))
+        .to_string();
+        assert_eq!(sql, "SELECT name, email FROM customers UNION SELECT name, email FROM suppliers WHERE active = 1");
+    }
+
+    #[test]
+    fn compound_order_limit_query() {
+        let (sql, params) = select(&["name"])
+        .from("a")
+        .where_(col("x").eq(value(1)))
+        .union_all(select(&["name"]).from("b"))
+        .intersect(select(&["name"]).from("c").where_(col("y").eq(value(2))))
+        .except(select(&["name"]).from("d"))
+        .order_by("name").desc()
+        .order_by("1")
+        .limit(Some(10))
+        .offset(None)
+        .to_sql_with_params();
+        assert_eq!(sql, "SELECT name FROM a WHERE x = ? UNION ALL SELECT name FROM b INTERSECT SELECT name FROM c WHERE y = ? EXCEPT SELECT name FROM d ORDER BY name DESC, 1 ASC LIMIT 10 OFFSET ?");
+        assert_eq!(params, vec![Value::Integer(1), Value::Integer(2)]);
+    }
+
+    #[test]
+    fn compound_group_having_query() {
+        let sql = select(&["dept", "count(*)"])
+        .from("employees")
+        .group_by(&["dept"])
+        .having("count(*) > 1")
+        .union(select(&["dept", "0"]).from("empty_depts"))
+        .limit(Some(5))
+        .to_string();
+        assert_eq!(sql, "SELECT dept, count(*) FROM employees GROUP BY dept HAVING count(*) > 1 UNION SELECT dept, 0 FROM empty_depts LIMIT 5");
+    }
+
+    #[test]
+    fn compound_sub_query() {
+        let sql = select(&["title"])
+        .from("books")
+        .where_(col("authorId").in_query(select(&["authorId"]).from("awards").union(select(&["authorId"]).from("nominations"))))
+        .to_string();
+        assert_eq!(sql, "SELECT title FROM books WHERE authorId IN (SELECT authorId FROM awards UNION SELECT authorId FROM nominations)");
+    }
+
+    #[test]
+    fn compound_column_count_mismatch() {
+        let query = select(&["name", "count(a, b)"]).from("a")
+        .union(select(&["name", "email"]).from("b"))
+        .union_all(select(&["name"]).from("c"));
+        assert_eq!(query.try_to_sql(), Err(Error::ResultColumnCount { arm: 2, expected: 2, found: 1 }));
+        assert_eq!(query.to_string(), "SELECT name, count(a, b) FROM a UNION SELECT name, email FROM b UNION ALL SELECT name FROM c");
+    }
+
+    #[test]
+    fn compound_arm_clauses() {
+        let query = select(&["name"]).from("a").union(select(&["name"]).from("b").limit(Some(1)));
+        assert_eq!(query.try_to_sql(), Err(Error::CompoundArmClause { arm: 1, clause: "LIMIT".into() }));
+        let query = select(&["name"]).from("a").offset(Some(2)).union(select(&["name"]).from("b"));
+        assert_eq!(query.try_to_sql(), Err(Error::CompoundArmClause { arm: 0, clause: "OFFSET".into() }));
+        let query = select(&["name"]).from("a").union(with("c", &[], select(&["name"]).from("b")).select(&["name"]).from("c"));
+        assert_eq!(query.try_to_sql(), Err(Error::CompoundArmClause { arm: 1, clause: "WITH".into() }));
+
+        let query = with("c", &[], select(&["name"]).from("b")).select(&["name"]).from("a").union(select(&["name"]).from("c"));
+        assert_eq!(query.try_to_sql(), Ok("WITH c AS (SELECT name FROM b) SELECT name FROM a UNION SELECT name FROM c".to_string()));
+    }
+
+    #[test]
+    fn compound_star_is_not_checked() {
+        let sql = select(&["*"]).from("a").union(select(&["x", "y"]).from("b")).to_string();
+        assert_eq!(sql, "SELECT * FROM a UNION SELECT x, y FROM b");
+    }
+}
@@ -1,19 +1,55 @@
 use std::fmt;
 
+use crate::expr::{self, Expr};
+use crate::{Statement, Value, Ident, Error};
+use crate::ident::TableName;
+use super::returning::Returning;
+use super::with::WithClause;
+use super::column::{TypedColumn, Operand};
+
 #[derive(Default)]
 struct RawUpdate {
-    table: String,
-    data: String,
-    where_: String
+    with: Option<WithClause>,
+    table: TableName,
+    data: Vec<(Ident, Expr)>,
+    where_: Option<Expr>,
+    returning: Returning
+}
+
+impl RawUpdate {
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(ref with) = self.with {
+            with.validate()?;
+        }
+        if self.data.is_empty() {
+            return Err(Error::NoAssignments { table: self.table.as_str().to_owned() });
+        }
+        Ok(())
+    }
+
+    fn collect_params(&self, params: &mut Vec<Value>) {
+        if let Some(ref with) = self.with {
+            with.collect_params(params);
+        }
+        self.data.iter().for_each(|it| it.1.collect_params(params));
+        if let Some(ref where_) = self.where_ {
+            where_.collect_params(params);
+        }
+        self.returning.collect_params(params);
+    }
 }
 
 impl fmt::Display for RawUpdate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "UPDATE {} SET {}", self.table, self.data)?;
-        if !self.where_.is_empty() {
-            write!(f," WHERE {}", self.where_)?;
+        let data = self.data.iter().map(|it| format!("{} = {}", it.0, it.1)).collect::<Vec<String>>().join(", ");
+        if let Some(ref with) = self.with {
+            write!(f, "{}", with)?;
         }
-        Ok(())
+        write!(f, "UPDATE {} SET {}", self.table, data)?;
+        if let Some(ref where_) = self.where_ {
+            write!(f," WHERE {}", where_)?;
+        }
+        write!(f, "{}", self.returning)
     }
 }
 
@@ -24,17 +60,39 @@ pub struct UpdateWhere(RawUpdate);
 
 
 impl Update {
-    pub fn set(mut self, data: &[(&str, &str)]) -> UpdateSet {
-        self.0.data = data.iter().map(|it| format!("{} = {}",it.0, it.1)).collect::<Vec<String>>().join(", ");
+    pub(super) fn with_clause(mut self, with: WithClause) -> Self {
+        self.0.with = Some(with);
+        self
+    }
+
+    /// Strings are assigned as raw SQL, [`Value`]s are bound to placeholders.
+    pub fn set<E>(mut self, data: &[(&str, E)]) -> UpdateSet where E: Into<Expr> + Clone {
+        self.0.data = data.iter().map(|it| (Ident::new(it.0), it.1.clone().into())).collect();
+        UpdateSet(self.0)
+    }
+
+    /// Typed assignment, `SET column = value`, further ones are added with [`UpdateSet::set_column`].
+    pub fn set_column<T>(mut self, column: TypedColumn<T>, value: impl Operand<T>) -> UpdateSet {
+        self.0.data.push((Ident::new(column.name()), value.into_expr()));
         UpdateSet(self.0)
     }
 }
 
 impl UpdateSet {
-    pub fn where_(mut self, cond: &str) -> UpdateWhere {
-        self.0.where_ = cond.to_string();
+    pub fn set_column<T>(mut self, column: TypedColumn<T>, value: impl Operand<T>) -> Self {
+        self.0.data.push((Ident::new(column.name()), value.into_expr()));
+        self
+    }
+
+    pub fn where_(mut self, cond: impl Into<Expr>) -> UpdateWhere {
+        self.0.where_ = Some(cond.into());
         UpdateWhere(self.0)
     }
+
+    pub fn returning<E>(mut self, columns: &[E]) -> Self where E: Into<Expr> + Clone {
+        self.0.returning.set(columns);
+        self
+    }
 }
 
 impl fmt::Display for UpdateSet {
@@ -43,14 +101,31 @@ impl fmt::Display for UpdateSet {
     }
 }
 
+impl Statement for UpdateSet {
+    fn params(&self) -> Vec<Value> {
+        let mut params = Vec::new();
+        self.0.collect_params(&mut params);
+        params
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.0.validate()
+    }
+}
+
 impl UpdateWhere {
-    pub fn and(mut self, cond: &str) -> Self {
-        self.0.where_.push_str(&format!(" AND {}", cond));
+    pub fn and(mut self, cond: impl Into<Expr>) -> Self {
+        expr::push_and(&mut self.0.where_, cond.into());
         self
     }
 
-    pub fn or(mut self, cond: &str) -> Self {
-        self.0.where_.push_str(&format!(" OR {}", cond));
+    pub fn or(mut self, cond: impl Into<Expr>) -> Self {
+        expr::push_or(&mut self.0.where_, cond.into());
+        self
+    }
+
+    pub fn returning<E>(mut self, columns: &[E]) -> Self where E: Into<Expr> + Clone {
+        self.0.returning.set(columns);
         self
     }
 }
@@ -61,9 +136,21 @@ impl fmt::Display for UpdateWhere {
     }
 }
 
+impl Statement for UpdateWhere {
+    fn params(&self) -> Vec<Value> {
+        let mut params = Vec::new();
+        self.0.collect_params(&mut params);
+        params
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.0.validate()
+    }
+}
+
 pub fn update(table: &str) -> Update {
     Update(RawUpdate{
-        table: table.to_string(),
+        table: TableName::new(table),
         ..Default::default()
     })
 }
@@ -89,6 +176,67 @@ mod test {
         or("customerId = 7").
         to_string();
         
-        assert_eq!(sql, "UPDATE transactions SET active = 0 WHERE amount > 100 AND amount < 1000 OR customerId = 7");
+        assert_eq!(sql, "UPDATE transactions SET active = 0 WHERE (amount > 100) AND (amount < 1000) OR (customerId = 7)");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn update_item_expr() {
+        use crate::expr::col;
+
+        let sql = update("transactions").
+        set(&[("active", "0")]).
+        where_(col("amount").gt(100)).
+        or(col("customerId").eq(7)).
+        and(col("amount").lt(1000)).
+        to_string();
+
+        assert_eq!(sql, "UPDATE transactions SET active = 0 WHERE (amount > 100 OR customerId = 7) AND amount < 1000");
+    }
+
+    #[test]
+    fn update_without_assignments() {
+        use crate::{Statement, Error};
+
+        let query = update("users").set::<&str>(&[]).where_("userId = 4");
+        assert_eq!(query.try_to_sql(), Err(Error::NoAssignments { table: "users".into() }));
+    }
+
+    #[test]
+    fn update_params() {
+        use crate::expr::{col, value};
+        use crate::{Statement, Value};
+
+        let (sql, params) = update("users").
+        set(&[("login", Value::from("a")), ("email", Value::from("a@a"))]).
+        where_(col("userId").eq(value(4))).
+        to_sql_with_params();
+
+        assert_eq!(sql, "UPDATE users SET login = ?, email = ? WHERE userId = ?");
+        assert_eq!(params, vec![Value::Text("a".into()), Value::Text("a@a".into()), Value::Integer(4)]);
+    }
+
+    #[test]
+    fn update_quoted() {
+        let sql = update("order").
+        set(&[("group", "1")]).
+        to_string();
+
+        assert_eq!(sql, "UPDATE \"order\" SET \"group\" = 1");
+    }
+
+    #[test]
+    fn update_returning() {
+        let sql = update("users").
+        set(&[("visits", "visits + 1")]).
+        returning(&["visits"]).
+        to_string();
+        assert_eq!(sql, "UPDATE users SET visits = visits + 1 RETURNING visits");
+
+        let sql = update("users").
+        set(&[("visits", "visits + 1")]).
+        where_("userId = 4").
+        returning(&["userId", "visits"]).
+        to_string();
+        assert_eq!(sql, "UPDATE users SET visits = visits + 1 WHERE userId = 4 RETURNING userId, visits");
+    }
+}
@@ -0,0 +1,365 @@
+use std::fmt;
+
+use crate::expr::{self, Expr};
+use crate::{Ident, Value};
+use crate::order::SortOrder;
+
+#[derive(Debug, Clone, Copy)]
+enum FrameUnit {
+    Rows,
+    Range,
+    Groups
+}
+
+impl fmt::Display for FrameUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Rows => write!(f, "ROWS"),
+            Self::Range => write!(f, "RANGE"),
+            Self::Groups => write!(f, "GROUPS")
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum BoundKind {
+    UnboundedPreceding,
+    Preceding(Expr),
+    CurrentRow,
+    Following(Expr),
+    UnboundedFollowing
+}
+
+/// Start or end of a window frame.
+#[derive(Debug, Clone)]
+pub struct FrameBound(BoundKind);
+
+impl FrameBound {
+    pub fn unbounded_preceding() -> Self {
+        Self(BoundKind::UnboundedPreceding)
+    }
+
+    pub fn preceding(offset: impl Into<Expr>) -> Self {
+        Self(BoundKind::Preceding(offset.into()))
+    }
+
+    pub fn current_row() -> Self {
+        Self(BoundKind::CurrentRow)
+    }
+
+    pub fn following(offset: impl Into<Expr>) -> Self {
+        Self(BoundKind::Following(offset.into()))
+    }
+
+    pub fn unbounded_following() -> Self {
+        Self(BoundKind::UnboundedFollowing)
+    }
+
+    fn collect_params(&self, params: &mut Vec<Value>) {
+        if let BoundKind::Preceding(ref e) | BoundKind::Following(ref e) = self.0 {
+            e.collect_params(params);
+        }
+    }
+}
+
+impl fmt::Display for FrameBound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            BoundKind::UnboundedPreceding => write!(f, "UNBOUNDED PRECEDING"),
+            BoundKind::Preceding(ref e) => write!(f, "{} PRECEDING", e),
+            BoundKind::CurrentRow => write!(f, "CURRENT ROW"),
+            BoundKind::Following(ref e) => write!(f, "{} FOLLOWING", e),
+            BoundKind::UnboundedFollowing => write!(f, "UNBOUNDED FOLLOWING")
+        }
+    }
+}
+
+/// Rows left out of a window frame, see <https://www.sqlite.org/windowfunctions.html#the_exclude_clause>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exclude {
+    NoOthers,
+    CurrentRow,
+    Group,
+    Ties
+}
+
+impl fmt::Display for Exclude {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::NoOthers => write!(f, "EXCLUDE NO OTHERS"),
+            Self::CurrentRow => write!(f, "EXCLUDE CURRENT ROW"),
+            Self::Group => write!(f, "EXCLUDE GROUP"),
+            Self::Ties => write!(f, "EXCLUDE TIES")
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Frame {
+    unit: FrameUnit,
+    start: FrameBound,
+    end: FrameBound,
+    exclude: Option<Exclude>
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} BETWEEN {} AND {}", self.unit, self.start, self.end)?;
+        if let Some(e) = self.exclude {
+            write!(f, " {}", e)?;
+        }
+        Ok(())
+    }
+}
+
+/// Window definition, used with [`Expr::over`] or named in the `WINDOW` clause of a select.
+#[derive(Debug, Clone, Default)]
+pub struct Window {
+    base: Option<Ident>,
+    partition_by: Vec<Expr>,
+    order_by: Vec<(Expr, SortOrder)>,
+    frame: Option<Frame>
+}
+
+impl Window {
+    /// Window extending the named one, `OVER (base ...)`.
+    pub fn named(base: &str) -> Self {
+        Self {
+            base: Some(Ident::new(base)),
+            ..Self::default()
+        }
+    }
+
+    pub fn partition_by(mut self, expr: impl Into<Expr>) -> Self {
+        self.partition_by.push(expr.into());
+        self
+    }
+
+    pub fn order_by(mut self, expr: impl Into<Expr>) -> Self {
+        self.order_by.push((expr.into(), SortOrder::Asc));
+        self
+    }
+
+    /// Sets the sort order of the most recently added `order_by` term.
+    pub fn asc(mut self) -> Self {
+        if let Some(value) = self.order_by.last_mut() {
+            value.1 = SortOrder::Asc;
+        }
+        self
+    }
+
+    /// Sets the sort order of the most recently added `order_by` term.
+    pub fn desc(mut self) -> Self {
+        if let Some(value) = self.order_by.last_mut() {
+            value.1 = SortOrder::Desc;
+        }
+        self
+    }
+
+    pub fn rows(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame(FrameUnit::Rows, start, end)
+    }
+
+    pub fn range(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame(FrameUnit::Range, start, end)
+    }
+
+    pub fn groups(self, start: FrameBound, end: FrameBound) -> Self {
+        self.frame(FrameUnit::Groups, start, end)
+    }
+
+    /// Sets the `EXCLUDE` clause of the frame, it has no effect when no frame is set.
+    pub fn exclude(mut self, exclude: Exclude) -> Self {
+        if let Some(ref mut frame) = self.frame {
+            frame.exclude = Some(exclude);
+        }
+        self
+    }
+
+    fn frame(mut self, unit: FrameUnit, start: FrameBound, end: FrameBound) -> Self {
+        self.frame = Some(Frame { unit, start, end, exclude: None });
+        self
+    }
+
+    /// Whether the window is only a reference to a named one, rendered without parentheses.
+    fn is_reference(&self) -> bool {
+        self.base.is_some() && self.partition_by.is_empty() && self.order_by.is_empty() && self.frame.is_none()
+    }
+
+    pub(crate) fn collect_params(&self, params: &mut Vec<Value>) {
+        self.partition_by.iter().for_each(|e| e.collect_params(params));
+        self.order_by.iter().for_each(|e| e.0.collect_params(params));
+        if let Some(ref frame) = self.frame {
+            frame.start.collect_params(params);
+            frame.end.collect_params(params);
+        }
+    }
+
+    /// Writes the definition inside the parentheses.
+    fn fmt_definition(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(ref base) = self.base {
+            parts.push(base.to_string());
+        }
+        if !self.partition_by.is_empty() {
+            parts.push(format!("PARTITION BY {}", self.partition_by.iter().map(|it| it.to_string()).collect::<Vec<String>>().join(", ")));
+        }
+        if !self.order_by.is_empty() {
+            parts.push(format!("ORDER BY {}", self.order_by.iter().map(|(e, o)| format!("{} {}", e, o)).collect::<Vec<String>>().join(", ")));
+        }
+        if let Some(ref frame) = self.frame {
+            parts.push(frame.to_string());
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Renders the window as the operand of `OVER`.
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.base {
+            Some(ref base) if self.is_reference() => write!(f, "{}", base),
+            _ => {
+                write!(f, "(")?;
+                self.fmt_definition(f)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Named reference to a window defined in the `WINDOW` clause.
+impl From<&str> for Window {
+    fn from(name: &str) -> Self {
+        Self::named(name)
+    }
+}
+
+/// Entry of the `WINDOW` clause of a select.
+#[derive(Debug, Clone)]
+pub(crate) struct NamedWindow(pub(crate) Ident, pub(crate) Window);
+
+impl fmt::Display for NamedWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} AS (", self.0)?;
+        self.1.fmt_definition(f)?;
+        write!(f, ")")
+    }
+}
+
+pub fn window() -> Window {
+    Window::default()
+}
+
+pub fn row_number() -> Expr {
+    expr::func("row_number", Vec::<Expr>::new())
+}
+
+pub fn rank() -> Expr {
+    expr::func("rank", Vec::<Expr>::new())
+}
+
+pub fn dense_rank() -> Expr {
+    expr::func("dense_rank", Vec::<Expr>::new())
+}
+
+pub fn percent_rank() -> Expr {
+    expr::func("percent_rank", Vec::<Expr>::new())
+}
+
+pub fn cume_dist() -> Expr {
+    expr::func("cume_dist", Vec::<Expr>::new())
+}
+
+pub fn ntile(groups: impl Into<Expr>) -> Expr {
+    expr::func("ntile", [groups.into()])
+}
+
+/// `lag(expr, offset, default)`, use [`func`](expr::func) for the shorter forms.
+pub fn lag(expr: impl Into<Expr>, offset: impl Into<Expr>, default: impl Into<Expr>) -> Expr {
+    expr::func("lag", [expr.into(), offset.into(), default.into()])
+}
+
+/// `lead(expr, offset, default)`, use [`func`](expr::func) for the shorter forms.
+pub fn lead(expr: impl Into<Expr>, offset: impl Into<Expr>, default: impl Into<Expr>) -> Expr {
+    expr::func("lead", [expr.into(), offset.into(), default.into()])
+}
+
+pub fn first_value(expr: impl Into<Expr>) -> Expr {
+    expr::func("first_value", [expr.into()])
+}
+
+pub fn last_value(expr: impl Into<Expr>) -> Expr {
+    expr::func("last_value", [expr.into()])
+}
+
+pub fn nth_value(expr: impl Into<Expr>, n: impl Into<Expr>) -> Expr {
+    expr::func("nth_value", [expr.into(), n.into()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{window, row_number, rank, lag, Window, FrameBound, Exclude};
+    use crate::expr::{col, func, value};
+    use crate::query::select;
+    use crate::{Statement, Value};
+
+    #[test]
+    fn over_partition_order() {
+        let e = row_number().over(window().partition_by(col("dept")).order_by(col("salary")).desc().order_by(col("id")));
+        assert_eq!(e.to_string(), "row_number() OVER (PARTITION BY dept ORDER BY salary DESC, id ASC)");
+    }
+
+    #[test]
+    fn over_empty_window() {
+        assert_eq!(func("count", [col("*")]).over(window()).to_string(), "count(*) OVER ()");
+    }
+
+    #[test]
+    fn frames() {
+        let w = window().order_by(col("day")).rows(FrameBound::preceding(6), FrameBound::current_row());
+        assert_eq!(func("avg", [col("amount")]).over(w).to_string(), "avg(amount) OVER (ORDER BY day ASC ROWS BETWEEN 6 PRECEDING AND CURRENT ROW)");
+
+        let w = window().order_by(col("day"))
+            .range(FrameBound::unbounded_preceding(), FrameBound::unbounded_following())
+            .exclude(Exclude::CurrentRow);
+        assert_eq!(w.to_string(), "(ORDER BY day ASC RANGE BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING EXCLUDE CURRENT ROW)");
+
+        let w = window().order_by(col("score")).groups(FrameBound::current_row(), FrameBound::following(1)).exclude(Exclude::Ties);
+        assert_eq!(w.to_string(), "(ORDER BY score ASC GROUPS BETWEEN CURRENT ROW AND 1 FOLLOWING EXCLUDE TIES)");
+    }
+
+    #[test]
+    fn filter() {
+        let e = func("count", [col("*")]).filter(col("status").eq(value("done"))).over(window().partition_by(col("project")));
+        assert_eq!(e.to_string(), "count(*) FILTER (WHERE status = ?) OVER (PARTITION BY project)");
+        assert_eq!(func("sum", [col("x")]).filter(col("x").gt(0)).to_string(), "sum(x) FILTER (WHERE x > 0)");
+    }
+
+    #[test]
+    fn named_windows() {
+        let (sql, params) = select(&["id"])
+            .column_as(rank().over("w"), "r")
+            .column(lag(col("amount"), 1, value(0)).over(Window::named("w").rows(FrameBound::preceding(1), FrameBound::current_row())))
+            .from("sales")
+            .where_(col("year").eq(value(2024)))
+            .window("w", window().partition_by(col("region")).order_by(col("amount")).desc())
+            .order_by("id")
+            .to_sql_with_params();
+        assert_eq!(sql, "SELECT id, rank() OVER w AS r, lag(amount, 1, ?) OVER (w ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) FROM sales WHERE year = ? WINDOW w AS (PARTITION BY region ORDER BY amount DESC) ORDER BY id ASC");
+        assert_eq!(params, vec![Value::Integer(0), Value::Integer(2024)]);
+    }
+
+    #[test]
+    fn window_after_having() {
+        let sql = select(&["dept"])
+            .column(func("sum", [func("sum", [col("salary")])]).over("w"))
+            .from("employees")
+            .group_by(&["dept"])
+            .having("count(*) > 1")
+            .window("w", window().order_by(col("dept")))
+            .window("v", Window::named("w").rows(FrameBound::unbounded_preceding(), FrameBound::current_row()))
+            .to_string();
+        assert_eq!(sql, "SELECT dept, sum(sum(salary)) OVER w FROM employees GROUP BY dept HAVING count(*) > 1 WINDOW w AS (ORDER BY dept ASC), v AS (w ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW)");
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt;
+
+use crate::{Ident, Value, Error};
+use crate::ident::{self, fmt_idents};
+use super::{Subquery, SelectQuery, Update, Insert, DeleteFrom, Columns};
+
+#[derive(Debug, Clone)]
+struct Cte {
+    name: Ident,
+    columns: Vec<Ident>,
+    materialized: Option<bool>,
+    query: Subquery,
+    recursive_query: Option<Subquery>
+}
+
+impl Cte {
+    fn new(name: &str, columns: &[&str], query: Subquery) -> Self {
+        Self {
+            name: Ident::new(name),
+            columns: ident::idents(columns),
+            materialized: None,
+            query,
+            recursive_query: None
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.query.validate()?;
+        if let Some(ref q) = self.recursive_query {
+            q.validate()?;
+            if let (Some(expected), Some(found)) = (self.query.result_columns(), q.result_columns()) {
+                if expected != found {
+                    return Err(Error::ResultColumnCount { arm: 1, expected, found });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn collect_params(&self, params: &mut Vec<Value>) {
+        self.query.collect_params(params);
+        if let Some(ref q) = self.recursive_query {
+            q.collect_params(params);
+        }
+    }
+}
+
+impl fmt::Display for Cte {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.columns.is_empty() {
+            write!(f, "({})", fmt_idents(&self.columns))?;
+        }
+        write!(f, " AS ")?;
+        match self.materialized {
+            Some(true) => write!(f, "MATERIALIZED ")?,
+            Some(false) => write!(f, "NOT MATERIALIZED ")?,
+            None => {}
+        }
+        write!(f, "({}", self.query)?;
+        if let Some(ref q) = self.recursive_query {
+            write!(f, " UNION ALL {}", q)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// `WITH` clause put in front of a select, insert, update or delete statement.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WithClause {
+    recursive: bool,
+    ctes: Vec<Cte>
+}
+
+impl WithClause {
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        self.ctes.iter().try_for_each(|c| c.validate())
+    }
+
+    pub(crate) fn collect_params(&self, params: &mut Vec<Value>) {
+        self.ctes.iter().for_each(|c| c.collect_params(params));
+    }
+}
+
+impl fmt::Display for WithClause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WITH ")?;
+        if self.recursive {
+            write!(f, "RECURSIVE ")?;
+        }
+        let ctes = self.ctes.iter().map(|it| it.to_string()).collect::<Vec<String>>().join(", ");
+        write!(f, "{} ", ctes)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct With(WithClause);
+
+impl With {
+    pub fn with(mut self, name: &str, columns: &[&str], query: impl Into<Subquery>) -> Self {
+        self.0.ctes.push(Cte::new(name, columns, query.into()));
+        self
+    }
+
+    /// Adds a recursive table, `initial` and `recursive` are combined with `UNION ALL`.
+    /// The whole clause becomes `WITH RECURSIVE`.
+    pub fn with_recursive(mut self, name: &str, columns: &[&str], initial: impl Into<Subquery>, recursive: impl Into<Subquery>) -> Self {
+        let mut cte = Cte::new(name, columns, initial.into());
+        cte.recursive_query = Some(recursive.into());
+        self.0.ctes.push(cte);
+        self.0.recursive = true;
+        self
+    }
+
+    /// Marks the most recently added table as `MATERIALIZED`.
+    pub fn materialized(mut self) -> Self {
+        if let Some(cte) = self.0.ctes.last_mut() {
+            cte.materialized = Some(true);
+        }
+        self
+    }
+
+    /// Marks the most recently added table as `NOT MATERIALIZED`.
+    pub fn not_materialized(mut self) -> Self {
+        if let Some(cte) = self.0.ctes.last_mut() {
+            cte.materialized = Some(false);
+        }
+        self
+    }
+
+    pub fn select(self, fields: impl Columns) -> SelectQuery {
+        super::select(fields).with_clause(self.0)
+    }
+
+    pub fn insert(self, columns: impl Columns) -> Insert {
+        super::insert(columns).with_clause(self.0)
+    }
+
+    pub fn replace(self, columns: impl Columns) -> Insert {
+        super::replace(columns).with_clause(self.0)
+    }
+
+    pub fn update(self, table: &str) -> Update {
+        super::update(table).with_clause(self.0)
+    }
+
+    pub fn delete_from(self, table: &str) -> DeleteFrom {
+        super::delete_from(table).with_clause(self.0)
+    }
+}
+
+pub fn with(name: &str, columns: &[&str], query: impl Into<Subquery>) -> With {
+    With(WithClause::default()).with(name, columns, query)
+}
+
+pub fn with_recursive(name: &str, columns: &[&str], initial: impl Into<Subquery>, recursive: impl Into<Subquery>) -> With {
+    With(WithClause::default()).with_recursive(name, columns, initial, recursive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{with, with_recursive};
+    use crate::query::select;
+    use crate::expr::{col, value};
+    use crate::{Statement, Value};
+
+    #[test]
+    fn with_select() {
+        let sql = with("adults", &[], select(&["*"]).from("people").where_(col("age").ge(18)))
+            .select(&["lastname"])
+            .from("adults")
+            .to_string();
+        assert_eq!(sql, "WITH adults AS (SELECT * FROM people WHERE age >= 18) SELECT lastname FROM adults");
+    }
+
+    #[test]
+    fn with_many_materialized() {
+        let sql = with("a", &["x"], select(&["id"]).from("t1")).materialized()
+            .with("b", &["y"], select(&["id"]).from("t2")).not_materialized()
+            .select(&["x", "y"])
+            .from("a")
+            .cross_join("b")
+            .to_string();
+        assert_eq!(sql, "WITH a(x) AS MATERIALIZED (SELECT id FROM t1), b(y) AS NOT MATERIALIZED (SELECT id FROM t2) SELECT x, y FROM a CROSS JOIN b");
+    }
+
+    #[test]
+    fn with_recursive_select() {
+        let (sql, params) = with_recursive("subordinates", &["id", "name"],
+                select(&["id", "name"]).from("employees").where_(col("id").eq(value(1))),
+                select(&["e.id", "e.name"]).from("employees").as_("e")
+                    .inner_join("subordinates").as_("s").on(col("e.managerId").eq(col("s.id"))))
+            .select(&["name"])
+            .from("subordinates")
+            .where_(col("id").ne(value(1)))
+            .to_sql_with_params();
+        assert_eq!(sql, "WITH RECURSIVE subordinates(id, name) AS (SELECT id, name FROM employees WHERE id = ? UNION ALL SELECT e.id, e.name FROM employees AS e INNER JOIN subordinates AS s ON e.managerId = s.id) SELECT name FROM subordinates WHERE id <> ?");
+        assert_eq!(params, vec![Value::Integer(1), Value::Integer(1)]);
+    }
+
+    #[test]
+    fn with_insert() {
+        let sql = with("src", &[], select(&["login"]).from("staging"))
+            .insert(&["login"])
+            .into("users")
+            .select(select(&["login"]).from("src"))
+            .to_string();
+        assert_eq!(sql, "WITH src AS (SELECT login FROM staging) INSERT INTO users(login) SELECT login FROM src");
+    }
+
+    #[test]
+    fn with_update() {
+        let sql = with("banned", &[], select(&["userId"]).from("bans"))
+            .update("users")
+            .set(&[("active", "0")])
+            .where_(col("userId").in_query(select(&["userId"]).from("banned")))
+            .to_string();
+        assert_eq!(sql, "WITH banned AS (SELECT userId FROM bans) UPDATE users SET active = 0 WHERE userId IN (SELECT userId FROM banned)");
+    }
+
+    #[test]
+    fn with_delete() {
+        let sql = with("banned", &[], select(&["userId"]).from("bans"))
+            .delete_from("comments")
+            .where_(col("userId").in_query(select(&["userId"]).from("banned")))
+            .to_string();
+        assert_eq!(sql, "WITH banned AS (SELECT userId FROM bans) DELETE FROM comments WHERE userId IN (SELECT userId FROM banned)");
+    }
+}
@@ -1,14 +1,16 @@
 use std::fmt;
 
-#[derive(Debug)]
+use crate::expr::Expr;
+
+#[derive(Debug, Clone)]
 pub struct Check {
-    expression: String
+    expression: Expr
 }
 
 impl Check {
-    pub fn new(constraint: &str) -> Self {
+    pub fn new(constraint: impl Into<Expr>) -> Self {
         Self {
-            expression: constraint.to_owned()
+            expression: constraint.into()
         }
     }
 }
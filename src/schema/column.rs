@@ -1,9 +1,20 @@
 use std::fmt;
 
+use crate::expr::Expr;
+use crate::{Ident, Error, Value, ConflictResolution};
+use crate::order::SortOrder;
+use super::{References, SqlType};
+
 const INT: &str = "INTEGER";
 const REAL: &str = "REAL";
 const TEXT: &str = "TEXT";
 const BLOB: &str = "BLOB";
+const ANY: &str = "ANY";
+const INT_SHORT: &str = "INT";
+const NUMERIC: &str = "NUMERIC";
+
+/// Column types allowed in `STRICT` tables.
+pub(crate) const STRICT_TYPES: [&str; 6] = [INT_SHORT, INT, REAL, TEXT, BLOB, ANY];
 
 #[derive(Debug, Clone, Copy)]
 pub enum GeneratedColumnType {
@@ -22,7 +33,7 @@ impl fmt::Display for GeneratedColumnType {
     
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Generated {
     expr: String,
     type_: GeneratedColumnType
@@ -34,32 +45,85 @@ impl Generated {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+enum DefaultValue {
+    /// Raw SQL, as given to `default_value`.
+    Raw(String),
+    Literal(Value),
+    Expr(Expr)
+}
+
+impl fmt::Display for DefaultValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Raw(v) => write!(f, "{}", v),
+            Self::Literal(v) => write!(f, "{}", v),
+            Self::Expr(e) => write!(f, "({})", e)
+        }
+    }
+}
+
+/// Column constraints, in the order they are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConstraintKind {
+    PrimaryKey,
+    NotNull,
+    Unique,
+    Check,
+    Default,
+    Collate,
+    References,
+    Generated
+}
+
+#[derive(Debug, Clone)]
 pub struct Column {
-    name: String,
+    name: Ident,
     typ_: String,
     not_null: bool,
     unique: bool,
     primary_key: bool,
-    check: Option<String>,
-    default_val: Option<String>,
-    generated: Option<Generated> 
+    pk_order: Option<SortOrder>,
+    autoincrement: bool,
+    check: Option<Expr>,
+    default_val: Option<DefaultValue>,
+    collation: Option<Ident>,
+    generated: Option<Generated>,
+    references: Option<References>,
+    names: Vec<(ConstraintKind, Ident)>,
+    conflicts: Vec<(ConstraintKind, ConflictResolution)>,
+    last: Option<ConstraintKind>
 }
 
 impl Column {
     pub fn new(name: &str) -> Self {
         Self {
-            name: name.to_string(),
+            name: Ident::new(name),
             typ_: INT.to_owned(),
             not_null: true,
             unique: false,
             primary_key: false,
+            pk_order: None,
+            autoincrement: false,
             check: None,
             default_val: None,
-            generated: None
+            collation: None,
+            generated: None,
+            references: None,
+            names: Vec::new(),
+            conflicts: Vec::new(),
+            last: None
         }
     }
 
+    /// Column of the SQLite type mapped from `T`, `Option<T>` makes it nullable.
+    pub fn of<T>(name: &str) -> Self where T: SqlType {
+        let mut col = Self::new(name).declared_type(T::TYPE);
+        col.not_null = !T::NULLABLE;
+        col.check = T::check(name);
+        col
+    }
+
     pub fn int(mut self) -> Self {
         self.typ_ = INT.to_string();
         self
@@ -80,32 +144,113 @@ impl Column {
         self
     }
 
-    pub fn nullable(mut self) -> Self {        
+    /// `ANY`, in `STRICT` tables the values are stored without any conversion.
+    pub fn any(mut self) -> Self {
+        self.typ_ = ANY.to_string();
+        self
+    }
+
+    /// `NUMERIC` affinity, values are stored as integers or reals when they can be converted losslessly.
+    pub fn numeric(mut self) -> Self {
+        self.typ_ = NUMERIC.to_string();
+        self
+    }
+
+    /// Any declared type, eg. `VARCHAR(255)` or `DATETIME`, the affinity follows from the name
+    /// as described in <https://www.sqlite.org/datatype3.html#determination_of_column_affinity>.
+    pub fn declared_type(mut self, typ: &str) -> Self {
+        self.typ_ = typ.to_string();
+        self
+    }
+
+    /// `INT`, the short spelling of `INTEGER`. Note that only `INTEGER PRIMARY KEY` is an alias of the rowid.
+    pub fn int_(mut self) -> Self {
+        self.typ_ = INT_SHORT.to_string();
+        self
+    }
+
+    pub fn nullable(mut self) -> Self {
         self.not_null = false;
         self.primary_key = false;
+        self.autoincrement = false;
         self.unique = false;
+        self.last = None;
+        self
+    }
+
+    /// Columns are `NOT NULL` by default, the method makes it the constraint modified by
+    /// [`name_constraint`](Column::name_constraint) and [`on_conflict`](Column::on_conflict).
+    pub fn not_null(mut self) -> Self {
+        self.not_null = true;
+        self.last = Some(ConstraintKind::NotNull);
         self
     }
 
-    pub fn unique(mut self) -> Self {       
-        self.not_null = true;       
+    pub fn unique(mut self) -> Self {
+        self.not_null = true;
         self.unique = true;
+        self.last = Some(ConstraintKind::Unique);
         self
     }
 
     pub fn primary_key(mut self) -> Self {
         self.not_null = true;
         self.primary_key = true;
+        self.last = Some(ConstraintKind::PrimaryKey);
+        self
+    }
+
+    /// `PRIMARY KEY ASC`
+    pub fn asc(mut self) -> Self {
+        self.pk_order = Some(SortOrder::Asc);
+        self
+    }
+
+    /// `PRIMARY KEY DESC`, note that `INTEGER PRIMARY KEY DESC` is not an alias of the rowid.
+    pub fn desc(mut self) -> Self {
+        self.pk_order = Some(SortOrder::Desc);
+        self
+    }
+
+    /// `INTEGER PRIMARY KEY AUTOINCREMENT`, rowids of deleted rows are never reused.
+    pub fn autoincrement(mut self) -> Self {
+        self.not_null = true;
+        self.primary_key = true;
+        self.autoincrement = true;
+        self.last = Some(ConstraintKind::PrimaryKey);
         self
     }
 
-    pub fn check(mut self, constraint: &str) -> Self {
-        self.check = Some(constraint.to_string());
+    pub fn check(mut self, constraint: impl Into<Expr>) -> Self {
+        self.check = Some(constraint.into());
+        self.last = Some(ConstraintKind::Check);
         self
     }
 
-    pub fn default_value(mut self, value: &str) -> Self {
-        self.default_val = Some(value.to_string());
+    /// Default given as raw SQL, eg. `default_value("21")` or `default_value("CURRENT_TIMESTAMP")`.
+    pub fn default_value(self, value: &str) -> Self {
+        self.default(DefaultValue::Raw(value.to_string()))
+    }
+
+    /// Literal default, `DEFAULT 'text'`.
+    pub fn default_literal(self, value: impl Into<Value>) -> Self {
+        self.default(DefaultValue::Literal(value.into()))
+    }
+
+    /// Expression default, always parenthesised, `DEFAULT (strftime('%s', 'now'))`.
+    pub fn default_expr(self, expr: impl Into<Expr>) -> Self {
+        self.default(DefaultValue::Expr(expr.into()))
+    }
+
+    fn default(mut self, value: DefaultValue) -> Self {
+        self.default_val = Some(value);
+        self.last = Some(ConstraintKind::Default);
+        self
+    }
+
+    pub fn collate(mut self, collation: &str) -> Self {
+        self.collation = Some(Ident::new(collation));
+        self.last = Some(ConstraintKind::Collate);
         self
     }
 
@@ -116,34 +261,167 @@ impl Column {
         self.generated = Some(Generated::new(expr, typ));
         self.default_val = None;
         self.primary_key = false;
+        self.autoincrement = false;
+        self.last = Some(ConstraintKind::Generated);
+        self
+    }
+
+    /// Column-level foreign key, `col INTEGER REFERENCES table (id)`.
+    pub fn references(mut self, refs: References) -> Self {
+        self.references = Some(refs);
+        self.last = Some(ConstraintKind::References);
+        self
+    }
+
+    /// Names the most recently added constraint, `CONSTRAINT name UNIQUE`.
+    pub fn name_constraint(mut self, name: &str) -> Self {
+        if let Some(kind) = self.last {
+            self.names.retain(|it| it.0 != kind);
+            self.names.push((kind, Ident::new(name)));
+        }
+        self
+    }
+
+    /// Conflict resolution of the most recently added constraint, only `PRIMARY KEY`, `NOT NULL`
+    /// and `UNIQUE` constraints can have one.
+    pub fn on_conflict(mut self, resolution: ConflictResolution) -> Self {
+        if let Some(kind) = self.last {
+            self.conflicts.retain(|it| it.0 != kind);
+            self.conflicts.push((kind, resolution));
+        }
         self
     }
 
     pub fn is_generated(&self) -> bool {
         self.generated.is_some()
     }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub(crate) fn is_primary_key(&self) -> bool {
+        self.primary_key
+    }
+
+    pub(crate) fn is_unique(&self) -> bool {
+        self.unique
+    }
+
+    pub(crate) fn references_clause(&self) -> Option<&References> {
+        self.references.as_ref()
+    }
+
+    pub(crate) fn is_autoincrement(&self) -> bool {
+        self.autoincrement
+    }
+
+    pub(crate) fn typ(&self) -> &str {
+        &self.typ_
+    }
+
+    /// Whether `ALTER TABLE ADD COLUMN` accepts the column whatever the rows of the table: it is not
+    /// a key or a stored generated column, its default is constant, `NULL` when it references a table
+    /// and not `NULL` when the column is `NOT NULL` and not generated.
+    pub(crate) fn can_be_added(&self) -> bool {
+        let (constant, null) = match self.default_val {
+            None => (true, true),
+            Some(DefaultValue::Raw(ref v)) => {
+                let v = v.trim().to_ascii_uppercase();
+                (!v.starts_with('(') && !v.starts_with("CURRENT_"), v == "NULL")
+            },
+            Some(DefaultValue::Literal(ref v)) => (true, *v == Value::Null),
+            Some(DefaultValue::Expr(_)) => (false, false)
+        };
+        let stored = matches!(self.generated, Some(Generated { type_: GeneratedColumnType::Stored, .. }));
+        let not_null = self.not_null && !self.is_generated();
+        !self.primary_key && !self.unique && !stored && constant && (null || self.references.is_none()) && !(null && not_null)
+    }
+
+    /// Whether `ALTER TABLE DROP COLUMN` accepts the column, as far as its own constraints go.
+    pub(crate) fn can_be_dropped(&self) -> bool {
+        !self.primary_key && !self.unique && self.references.is_none()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.primary_key && self.is_generated() {
+            return Err(Error::GeneratedPrimaryKey { column: self.name.as_str().to_owned() });
+        }
+        let rowid_alias = self.primary_key && self.typ_.eq_ignore_ascii_case(INT) && self.pk_order != Some(SortOrder::Desc);
+        if self.autoincrement && !rowid_alias {
+            return Err(Error::Autoincrement { column: self.name.as_str().to_owned() });
+        }
+        let allowed = [ConstraintKind::PrimaryKey, ConstraintKind::NotNull, ConstraintKind::Unique];
+        if self.conflicts.iter().any(|it| !allowed.contains(&it.0)) {
+            return Err(Error::ConflictClause { column: self.name.as_str().to_owned() });
+        }
+        match self.references {
+            Some(ref refs) => refs.validate(std::slice::from_ref(&self.name)),
+            None => Ok(())
+        }
+    }
+
+    /// Writes ` [CONSTRAINT name] ` in front of a constraint.
+    fn fmt_name(&self, f: &mut fmt::Formatter, kind: ConstraintKind) -> fmt::Result {
+        write!(f, " ")?;
+        if let Some((_, n)) = self.names.iter().find(|it| it.0 == kind) {
+            write!(f, "CONSTRAINT {} ", n)?;
+        }
+        Ok(())
+    }
+
+    fn fmt_conflict(&self, f: &mut fmt::Formatter, kind: ConstraintKind) -> fmt::Result {
+        if let Some((_, c)) = self.conflicts.iter().find(|it| it.0 == kind) {
+            write!(f, " ON CONFLICT {}", c)?;
+        }
+        Ok(())
+    }
 }
 
+/// Renders the column definition, constraints follow the order of the SQLite grammar.
 impl fmt::Display for Column {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{} {}", self.name, self.typ_)?;
+        if self.primary_key {
+            self.fmt_name(f, ConstraintKind::PrimaryKey)?;
+            write!(f, "PRIMARY KEY")?;
+            if let Some(o) = self.pk_order {
+                write!(f, " {}", o)?;
+            }
+            self.fmt_conflict(f, ConstraintKind::PrimaryKey)?;
+            if self.autoincrement {
+                write!(f, " AUTOINCREMENT")?;
+            }
+        }
         if self.not_null {
-            write!(f, " NOT NULL")?;
+            self.fmt_name(f, ConstraintKind::NotNull)?;
+            write!(f, "NOT NULL")?;
+            self.fmt_conflict(f, ConstraintKind::NotNull)?;
         }
         if self.unique {
-            write!(f, " UNIQUE")?;
+            self.fmt_name(f, ConstraintKind::Unique)?;
+            write!(f, "UNIQUE")?;
+            self.fmt_conflict(f, ConstraintKind::Unique)?;
         }
-        if self.primary_key {
-            write!(f, " PRIMARY KEY")?;
+        if let Some(ref c) = self.check {
+            self.fmt_name(f, ConstraintKind::Check)?;
+            write!(f, "CHECK({})", c)?;
         }
         if let Some(ref v) = self.default_val {
-            write!(f, " DEFAULT {}", v)?;
+            self.fmt_name(f, ConstraintKind::Default)?;
+            write!(f, "DEFAULT {}", v)?;
         }
-        if let Some(ref c) = self.check {
-            write!(f, " CHECK({})", c)?;
+        if let Some(ref c) = self.collation {
+            self.fmt_name(f, ConstraintKind::Collate)?;
+            write!(f, "COLLATE {}", c)?;
+        }
+        if let Some(ref r) = self.references {
+            self.fmt_name(f, ConstraintKind::References)?;
+            write!(f, "{}", r)?;
         }
         if let Some(ref g) = self.generated {
-            write!(f, " AS ({}) {}", g.expr, g.type_)?;
+            self.fmt_name(f, ConstraintKind::Generated)?;
+            write!(f, "AS ({}) {}", g.expr, g.type_)?;
         }
         Ok(())
     }
@@ -152,11 +430,20 @@ impl fmt::Display for Column {
 #[cfg(test)]
 mod tests {
     use super::{Column, GeneratedColumnType};
+    use crate::schema::{References, ForeignKeyAction};
+    use crate::{Error, ConflictResolution};
+    use crate::expr::{col, lit, func};
 
     #[test]
     fn primary_key() {
         let col = Column::new("Id").primary_key();
-        assert_eq!(col.to_string(), "Id INTEGER NOT NULL PRIMARY KEY");
+        assert_eq!(col.to_string(), "Id INTEGER PRIMARY KEY NOT NULL");
+    }
+
+    #[test]
+    fn quoted_name() {
+        let col = Column::new("order").text();
+        assert_eq!(col.to_string(), "\"order\" TEXT NOT NULL");
     }
 
     #[test]
@@ -177,6 +464,12 @@ mod tests {
         assert_eq!(col.to_string(), "Col INTEGER NOT NULL CHECK(Col > 10)");
     }
 
+    #[test]
+    fn check_expr() {
+        let c = Column::new("Col").text().check(col("Col").in_list([lit("a"), lit("b")]));
+        assert_eq!(c.to_string(), "Col TEXT NOT NULL CHECK(Col IN ('a', 'b'))");
+    }
+
     #[test]
     fn derfault_value() {
         let col = Column::new("Col").default_value("21");
@@ -184,18 +477,99 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn generated() {
         let col1 = Column::new("Generated_1").primary_key().generated("x + y + 1", GeneratedColumnType::Virtual);
         let col2 = Column::new("Generated_2").default_value("10").generated("x + y + 1", GeneratedColumnType::Stored);
         assert_eq!(col1.primary_key, false);
-        assert_eq!(col1.default_val, None);
+        assert!(col1.default_val.is_none());
         assert_eq!(col1.to_string(), "Generated_1 INTEGER NOT NULL AS (x + y + 1) VIRTUAL");
         assert_eq!(col2.primary_key, false);
-        assert_eq!(col2.default_val, None);
+        assert!(col2.default_val.is_none());
         assert_eq!(col2.to_string(), "Generated_2 INTEGER NOT NULL AS (x + y + 1) STORED");
     }
 
     #[test]
+    fn generated_primary_key() {
+        let col = Column::new("Generated").generated("x + 1", GeneratedColumnType::Virtual).primary_key();
+        assert_eq!(col.validate(), Err(Error::GeneratedPrimaryKey { column: "Generated".into() }));
+        assert_eq!(Column::new("Id").primary_key().validate(), Ok(()));
+    }
+
+    #[test]
+    fn autoincrement() {
+        let col = Column::new("id").autoincrement();
+        assert_eq!(col.to_string(), "id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL");
+        assert_eq!(col.validate(), Ok(()));
+
+        let col = Column::new("id").int_().autoincrement();
+        assert_eq!(col.validate(), Err(Error::Autoincrement { column: "id".into() }));
+        assert_eq!(Column::new("id").autoincrement().text().validate(), Err(Error::Autoincrement { column: "id".into() }));
+        assert_eq!(Column::new("id").autoincrement().desc().validate(), Err(Error::Autoincrement { column: "id".into() }));
+        assert_eq!(Column::new("id").declared_type("integer").autoincrement().asc().validate(), Ok(()));
+    }
+
+    #[test]
+    fn strict_types() {
+        assert_eq!(Column::new("a").any().to_string(), "a ANY NOT NULL");
+        assert_eq!(Column::new("a").int_().nullable().to_string(), "a INT");
+    }
+
+    #[test]
+    fn declared_types() {
+        assert_eq!(Column::new("amount").numeric().to_string(), "amount NUMERIC NOT NULL");
+        assert_eq!(Column::new("email").declared_type("VARCHAR(255)").to_string(), "email VARCHAR(255) NOT NULL");
+    }
+
+    #[test]
+    fn references() {
+        let col = Column::new("deptId").nullable().references(References::new("depts", &["id"]).on_delete(ForeignKeyAction::SetNull).initially_deferred());
+        assert_eq!(col.to_string(), "deptId INTEGER REFERENCES depts (id) ON DELETE SET NULL DEFERRABLE INITIALLY DEFERRED");
+        assert_eq!(col.validate(), Ok(()));
+
+        let col = Column::new("deptId").references(References::new("depts", &["id", "tenant"]));
+        assert_eq!(col.validate(), Err(Error::ReferenceColumnCount { column: "deptId".into(), expected: 1, found: 2 }));
+    }
+
+    #[test]
+    fn constraint_options() {
+        let c = Column::new("id").primary_key().desc().on_conflict(ConflictResolution::Replace).name_constraint("pk_id");
+        assert_eq!(c.to_string(), "id INTEGER CONSTRAINT pk_id PRIMARY KEY DESC ON CONFLICT REPLACE NOT NULL");
+
+        let c = Column::new("email").text()
+            .not_null().on_conflict(ConflictResolution::Abort)
+            .unique().name_constraint("uq_email").on_conflict(ConflictResolution::Ignore)
+            .collate("NOCASE")
+            .check(col("email").like(lit("%@%"))).name_constraint("ck_email");
+        assert_eq!(c.to_string(), "email TEXT NOT NULL ON CONFLICT ABORT CONSTRAINT uq_email UNIQUE ON CONFLICT IGNORE CONSTRAINT ck_email CHECK(email LIKE '%@%') COLLATE NOCASE");
+        assert_eq!(c.validate(), Ok(()));
+
+        let c = Column::new("a").check("a > 0").on_conflict(ConflictResolution::Fail);
+        assert_eq!(c.validate(), Err(Error::ConflictClause { column: "a".into() }));
+    }
+
+    #[test]
+    fn defaults() {
+        assert_eq!(Column::new("name").text().default_literal("O'Hara").to_string(), "name TEXT NOT NULL DEFAULT 'O''Hara'");
+        assert_eq!(Column::new("n").default_literal(-1).to_string(), "n INTEGER NOT NULL DEFAULT -1");
+        let c = Column::new("created").default_expr(func("strftime", [lit("%s"), lit("now")])).name_constraint("df_created");
+        assert_eq!(c.to_string(), "created INTEGER NOT NULL CONSTRAINT df_created DEFAULT (strftime('%s', 'now'))");
+    }
+
+    #[test]
+    fn grammar_order() {
+        let c = Column::new("deptId")
+            .references(References::new("depts", &["id"]))
+            .collate("BINARY")
+            .default_value("0")
+            .check("deptId >= 0")
+            .unique()
+            .primary_key();
+        assert_eq!(c.to_string(), "deptId INTEGER PRIMARY KEY NOT NULL UNIQUE CHECK(deptId >= 0) DEFAULT 0 COLLATE BINARY REFERENCES depts (id)");
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn is_generated() {
         let col1 = Column::new("col1").generated("1 + x", GeneratedColumnType::Stored);
         let col2 = Column::new("col2");
@@ -0,0 +1,292 @@
+use std::fmt;
+
+use super::{Table, Index};
+use super::view::{ViewCreateStmt, referenced_tables};
+use super::triggers::TriggerFull;
+use crate::{Statement, Error};
+
+/// Tables, views, indexes and triggers of a database.
+///
+/// Validation checks the references between them and the script creates them in dependency order:
+/// tables before the tables referencing them, then views, indexes and triggers.
+/// Names are compared case-insensitively, as SQLite does.
+#[derive(Default)]
+pub struct Database {
+    tables: Vec<Table>,
+    views: Vec<ViewCreateStmt>,
+    indexes: Vec<Index>,
+    triggers: Vec<TriggerFull>
+}
+
+impl Database {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_table(&mut self, table: Table) -> &mut Self {
+        self.tables.push(table);
+        self
+    }
+
+    pub fn add_view(&mut self, view: ViewCreateStmt) -> &mut Self {
+        self.views.push(view);
+        self
+    }
+
+    /// Index created after all the tables, indexes added with [`Table::add_index`] are created with their table.
+    pub fn add_index(&mut self, index: Index) -> &mut Self {
+        self.indexes.push(index);
+        self
+    }
+
+    pub fn add_trigger(&mut self, trigger: TriggerFull) -> &mut Self {
+        self.triggers.push(trigger);
+        self
+    }
+
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        self.tables.iter().find(|t| t.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    fn view(&self, name: &str) -> Option<&ViewCreateStmt> {
+        self.views.iter().find(|v| v.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Indexes added with [`add_index`](Database::add_index).
+    pub(crate) fn indexes(&self) -> &[Index] {
+        &self.indexes
+    }
+
+    pub(crate) fn triggers(&self) -> &[TriggerFull] {
+        &self.triggers
+    }
+
+    /// Tables, each after the tables its foreign keys reference.
+    pub(crate) fn ordered_tables(&self) -> Vec<&Table> {
+        let deps = self.tables.iter()
+            .map(|t| t.references().into_iter().map(|(_, r)| r.table().as_str().to_owned()).collect())
+            .collect::<Vec<Vec<String>>>();
+        let names = self.tables.iter().map(|t| t.name()).collect::<Vec<&str>>();
+        dependency_order(&names, &deps).into_iter().map(|i| &self.tables[i]).collect()
+    }
+
+    /// Views, each after the views it selects from.
+    pub(crate) fn ordered_views(&self) -> Vec<&ViewCreateStmt> {
+        let deps = self.views.iter().map(|v| referenced_tables(v.select())).collect::<Vec<Vec<String>>>();
+        let names = self.views.iter().map(|v| v.name()).collect::<Vec<&str>>();
+        dependency_order(&names, &deps).into_iter().map(|i| &self.views[i]).collect()
+    }
+
+    fn validate_names(&self) -> Result<(), Error> {
+        let mut names = self.tables.iter().map(|t| t.name())
+            .chain(self.views.iter().map(|v| v.name()))
+            .chain(self.tables.iter().flat_map(|t| t.indexes()).map(|i| i.name()))
+            .chain(self.indexes.iter().map(|i| i.name()))
+            .collect::<Vec<&str>>();
+        let mut triggers = self.triggers.iter().map(|t| t.name()).collect::<Vec<&str>>();
+        for names in [&mut names, &mut triggers] {
+            names.sort_by_key(|n| n.to_ascii_lowercase());
+            if let Some(w) = names.windows(2).find(|w| w[0].eq_ignore_ascii_case(w[1])) {
+                return Err(Error::DuplicateObject { name: w[0].to_owned() });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_foreign_keys(&self, table: &Table) -> Result<(), Error> {
+        for (_, refs) in table.references() {
+            let parent = match self.table(refs.table().as_str()) {
+                Some(parent) => parent,
+                None => return Err(Error::UnknownTable { object: table.name().to_owned(), table: refs.table().as_str().to_owned() })
+            };
+            let columns = match refs.columns() {
+                [] => parent.primary_key_columns(),
+                columns => columns.iter().map(|c| c.as_str()).collect()
+            };
+            if let Some(column) = columns.iter().find(|&&c| !has_column(parent, c)) {
+                return Err(Error::UnknownColumn { table: parent.name().to_owned(), column: (*column).to_owned() });
+            }
+            let is_key = |key: &Vec<&str>| !key.is_empty() && key.len() == columns.len()
+                && key.iter().all(|k| columns.iter().any(|c| c.eq_ignore_ascii_case(k)));
+            let indexes = self.indexes.iter()
+                .filter(|i| i.is_unique_key() && i.table().as_str().eq_ignore_ascii_case(parent.name()))
+                .map(|i| i.column_names());
+            let mut keys = parent.unique_keys().into_iter().chain(indexes);
+            if !is_key(&parent.primary_key_columns()) && !keys.any(|k| is_key(&k)) {
+                return Err(Error::ForeignKeyMismatch { table: table.name().to_owned(), referenced: parent.name().to_owned() });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn has_column(table: &Table, column: &str) -> bool {
+    table.columns().iter().any(|c| c.name().eq_ignore_ascii_case(column))
+}
+
+/// Indexes of the items ordered so that each comes after the items it depends on. Dependencies on
+/// unknown names or on the item itself are ignored, items of a cycle keep their declaration order.
+fn dependency_order(names: &[&str], deps: &[Vec<String>]) -> Vec<usize> {
+    let mut order: Vec<usize> = Vec::with_capacity(names.len());
+    while order.len() < names.len() {
+        let ready = (0..names.len()).filter(|i| !order.contains(i)).find(|&i| {
+            deps[i].iter().all(|d| {
+                d.eq_ignore_ascii_case(names[i]) || names.iter().enumerate().all(|(j, n)| !n.eq_ignore_ascii_case(d) || order.contains(&j))
+            })
+        });
+        let next = ready.unwrap_or_else(|| (0..names.len()).find(|i| !order.contains(i)).expect("an item left"));
+        order.push(next);
+    }
+    order
+}
+
+impl Statement for Database {
+    fn validate(&self) -> Result<(), Error> {
+        self.tables.iter().try_for_each(|t| t.validate())?;
+        self.views.iter().try_for_each(|v| v.validate())?;
+        self.indexes.iter().try_for_each(|i| i.validate())?;
+        self.triggers.iter().try_for_each(|t| t.validate())?;
+        self.validate_names()?;
+        for table in &self.tables {
+            self.validate_foreign_keys(table)?;
+        }
+        for index in &self.indexes {
+            let table = match self.table(index.table().as_str()) {
+                Some(table) => table,
+                None => return Err(Error::UnknownTable { object: index.name().to_owned(), table: index.table().as_str().to_owned() })
+            };
+            if let Some(column) = index.column_names().into_iter().find(|c| !has_column(table, c)) {
+                return Err(Error::UnknownColumn { table: table.name().to_owned(), column: column.to_owned() });
+            }
+        }
+        for view in &self.views {
+            let unknown = referenced_tables(view.select()).into_iter()
+                .find(|t| self.table(t).is_none() && self.view(t).is_none() && !t.to_ascii_lowercase().starts_with("sqlite_"));
+            if let Some(table) = unknown {
+                return Err(Error::UnknownTable { object: view.name().to_owned(), table });
+            }
+        }
+        for trigger in &self.triggers {
+            let target = trigger.table().as_str();
+            let (is_table, is_view) = (self.table(target).is_some(), self.view(target).is_some());
+            if !is_table && !is_view {
+                return Err(Error::UnknownTable { object: trigger.name().to_owned(), table: target.to_owned() });
+            }
+            if trigger.is_instead_of() != is_view {
+                return Err(Error::TriggerTarget { trigger: trigger.name().to_owned(), target: target.to_owned() });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Creation script, one statement per line.
+impl fmt::Display for Database {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let statements = self.ordered_tables().into_iter().map(|t| t.to_string())
+            .chain(self.ordered_views().into_iter().map(|v| v.to_string()))
+            .chain(self.indexes.iter().map(|i| i.to_string()))
+            .chain(self.triggers.iter().map(|t| t.to_string()))
+            .collect::<Vec<String>>();
+        write!(f, "{}", statements.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Database;
+    use crate::schema::{create_table, create_view, create_index, create_trigger, Column, ForeignKey, References};
+    use crate::{Statement, Error};
+
+    fn users() -> crate::schema::Table {
+        let mut users = create_table("users");
+        users.add_column(Column::new("id").primary_key()).add_column(Column::new("email").text().unique());
+        users
+    }
+
+    #[test]
+    fn dependency_order() {
+        let mut comments = create_table("comments");
+        comments.add_column(Column::new("post_id").references(References::new("posts", &[])));
+        let mut posts = create_table("posts");
+        posts.add_column(Column::new("id").primary_key())
+            .add_column(Column::new("author"))
+            .add_foreign_key(ForeignKey::new("author").references("users", "id"));
+
+        let mut db = Database::new();
+        db.add_view(create_view("recent").as_("SELECT * FROM posts_by_author LIMIT 10"))
+            .add_view(create_view("posts_by_author").as_("SELECT p.* FROM posts p JOIN users u ON u.id = p.author"))
+            .add_trigger(create_trigger("no_delete").before().delete().on("users").statement("SELECT RAISE(ABORT, 'no')"))
+            .add_index(create_index("idx_author").on("posts").column("author"))
+            .add_table(comments)
+            .add_table(posts)
+            .add_table(users());
+        assert_eq!(db.validate(), Ok(()));
+        let names = db.to_string().lines()
+            .filter(|l| l.starts_with("CREATE"))
+            .map(|l| l.split(' ').find(|w| !w.chars().all(|c| c.is_ascii_uppercase())).unwrap_or_default().to_owned())
+            .collect::<Vec<String>>();
+        assert_eq!(names, ["users", "posts", "comments", "posts_by_author", "recent", "idx_author", "no_delete"]);
+    }
+
+    #[test]
+    fn unknown_references() {
+        let mut db = Database::new();
+        let mut orders = create_table("orders");
+        orders.add_column(Column::new("user_id").references(References::new("customers", &["id"])));
+        db.add_table(users()).add_table(orders);
+        assert_eq!(db.validate(), Err(Error::UnknownTable { object: "orders".into(), table: "customers".into() }));
+
+        let mut db = Database::new();
+        let mut orders = create_table("orders");
+        orders.add_column(Column::new("user_email").references(References::new("users", &["mail"])));
+        db.add_table(users()).add_table(orders);
+        assert_eq!(db.validate(), Err(Error::UnknownColumn { table: "users".into(), column: "mail".into() }));
+
+        let mut db = Database::new();
+        db.add_table(users()).add_view(create_view("v").as_("SELECT * FROM users JOIN groups USING (id)"));
+        assert_eq!(db.validate(), Err(Error::UnknownTable { object: "v".into(), table: "groups".into() }));
+
+        let mut db = Database::new();
+        db.add_table(users()).add_index(create_index("idx").on("users").column("name"));
+        assert_eq!(db.validate(), Err(Error::UnknownColumn { table: "users".into(), column: "name".into() }));
+    }
+
+    #[test]
+    fn foreign_key_mismatch() {
+        let mut orders = create_table("orders");
+        orders.add_column(Column::new("user_email").text().references(References::new("users", &["email"])));
+        let mut db = Database::new();
+        db.add_table(users()).add_table(orders);
+        assert_eq!(db.validate(), Ok(()));
+
+        let mut users = create_table("users");
+        users.add_column(Column::new("id").primary_key()).add_column(Column::new("email").text());
+        let mut orders = create_table("orders");
+        orders.add_column(Column::new("user_email").text().references(References::new("users", &["email"])));
+        let mut db = Database::new();
+        db.add_table(users).add_table(orders);
+        assert_eq!(db.validate(), Err(Error::ForeignKeyMismatch { table: "orders".into(), referenced: "users".into() }));
+        db.add_index(create_index("uq_email").unique().on("users").column("email"));
+        assert_eq!(db.validate(), Ok(()));
+    }
+
+    #[test]
+    fn names_and_triggers() {
+        let mut db = Database::new();
+        db.add_table(users()).add_view(create_view("Users").as_("SELECT 1"));
+        assert_eq!(db.validate(), Err(Error::DuplicateObject { name: "users".into() }));
+
+        let mut db = Database::new();
+        db.add_table(users()).add_trigger(create_trigger("t").instead_of().insert().on("users").statement("SELECT 1"));
+        assert_eq!(db.validate(), Err(Error::TriggerTarget { trigger: "t".into(), target: "users".into() }));
+
+        let mut db = Database::new();
+        db.add_trigger(create_trigger("t").after().insert().on("users").statement("SELECT 1"));
+        assert_eq!(db.validate(), Err(Error::UnknownTable { object: "t".into(), table: "users".into() }));
+    }
+}
@@ -0,0 +1,550 @@
+use std::fmt;
+
+use super::{Table, Database, alter_table, drop_table, drop_view, drop_index, drop_trigger};
+use super::table::AlterTable;
+use crate::query::{insert, select};
+use crate::{Ident, Statement, Error};
+
+/// Statements migrating a table to a new definition of it, see [`diff_tables`].
+///
+/// Columns are matched by name. Renames, additions and removals SQLite supports are made with
+/// `ALTER TABLE`, other changes, eg. of a column type, a constraint or a foreign key, rebuild the table.
+/// Column order is not compared, added columns come last.
+pub struct TableDiff<'a> {
+    old: &'a Table,
+    new: &'a Table,
+    renames: Vec<(Ident, Ident)>
+}
+
+impl<'a> TableDiff<'a> {
+    fn new(old: &'a Table, new: &'a Table) -> Self {
+        Self { old, new, renames: Vec::new() }
+    }
+
+    /// Takes `new_col` for `col` renamed, rather than `col` dropped and `new_col` added.
+    pub fn rename_column(&mut self, col: &str, new_col: &str) -> &mut Self {
+        self.renames.push((Ident::new(col), Ident::new(new_col)));
+        self
+    }
+
+    /// Whether `ALTER TABLE` cannot make the change and the table is rebuilt.
+    pub fn requires_rebuild(&self) -> bool {
+        self.alter().is_none()
+    }
+
+    /// The statements, none when the definitions are the same.
+    pub fn statements(&self) -> Vec<String> {
+        let alter = match self.alter() {
+            Some(alter) => alter,
+            None => {
+                let copy = self.new.columns().iter()
+                    .filter(|c| !c.is_generated())
+                    .filter_map(|c| self.source(c.name()).map(|s| (c.name(), s)))
+                    .collect::<Vec<(&str, &str)>>();
+                return rebuild(self.old.name(), self.new, &copy);
+            }
+        };
+        let old_indexes = self.old.indexes().iter().map(|i| (i.name(), self.renamed(&i.to_string()))).collect::<Vec<(&str, String)>>();
+        let new_indexes = self.new.indexes().iter().map(|i| i.to_string()).collect::<Vec<String>>();
+        let mut statements = old_indexes.iter()
+            .filter(|(_, sql)| !new_indexes.contains(sql))
+            .map(|(name, _)| drop_index(name).to_string())
+            .collect::<Vec<String>>();
+        let alter = alter.to_string();
+        if !alter.is_empty() {
+            statements.push(alter);
+        }
+        statements.extend(new_indexes.into_iter().filter(|sql| !old_indexes.iter().any(|(_, old)| old == sql)));
+        statements
+    }
+
+    /// Name of the old column a column of the new table comes from.
+    fn source(&self, column: &str) -> Option<&'a str> {
+        let name = match self.renames.iter().find(|(_, to)| to.as_str().eq_ignore_ascii_case(column)) {
+            Some((from, _)) => from.as_str(),
+            None if self.renames.iter().any(|(from, _)| from.as_str().eq_ignore_ascii_case(column)) => return None,
+            None => column
+        };
+        self.old.columns().iter().find(|c| c.name().eq_ignore_ascii_case(name)).map(|c| c.name())
+    }
+
+    /// SQL of the old table with the renamed columns and table.
+    fn renamed(&self, sql: &str) -> String {
+        let mut renames = self.renames.iter().map(|(from, to)| (from.as_str(), to)).collect::<Vec<(&str, &Ident)>>();
+        let table = Ident::new(self.new.name());
+        if !self.old.name().eq_ignore_ascii_case(self.new.name()) {
+            renames.push((self.old.name(), &table));
+        }
+        rename_identifiers(sql, &renames)
+    }
+
+    /// `ALTER TABLE` making the change, `None` when the table has to be rebuilt.
+    fn alter(&self) -> Option<AlterTable> {
+        if self.old.is_strict() != self.new.is_strict() || self.old.is_without_rowid() != self.new.is_without_rowid() {
+            return None;
+        }
+        let constraints = self.old.constraints().iter().map(|c| normalized(&self.renamed(c))).collect::<Vec<String>>();
+        if constraints != self.new.constraints().iter().map(|c| normalized(c)).collect::<Vec<String>>() {
+            return None;
+        }
+        let mut alter = alter_table(self.old.name());
+        let mut sources = Vec::new();
+        for col in self.new.columns() {
+            match self.source(col.name()).and_then(|s| self.old.columns().iter().find(|c| c.name() == s)) {
+                Some(old) => {
+                    if normalized(&self.renamed(&old.to_string())) != normalized(&col.to_string()) {
+                        return None;
+                    }
+                    if old.name() != col.name() {
+                        alter.rename_column(old.name(), col.name());
+                    }
+                    sources.push(old.name());
+                },
+                None if col.can_be_added() => {
+                    alter.add_column(col.clone());
+                },
+                None => return None
+            }
+        }
+        for col in self.old.columns().iter().filter(|c| !sources.contains(&c.name())) {
+            if !col.can_be_dropped() {
+                return None;
+            }
+            alter.drop_column(col.name());
+        }
+        if self.old.name() != self.new.name() {
+            alter.rename_to(self.new.name());
+        }
+        Some(alter)
+    }
+}
+
+impl Statement for TableDiff<'_> {
+    fn has_transaction(&self) -> bool {
+        self.requires_rebuild()
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.old.validate()?;
+        self.new.validate()?;
+        for (from, to) in &self.renames {
+            for (table, column) in [(self.old, from), (self.new, to)] {
+                if !table.columns().iter().any(|c| c.name().eq_ignore_ascii_case(column.as_str())) {
+                    return Err(Error::UnknownColumn { table: table.name().to_owned(), column: column.as_str().to_owned() });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TableDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.statements().join("\n"))
+    }
+}
+
+/// Statements migrating a database to a new schema, see [`diff_databases`].
+///
+/// Objects are matched by name. Tables are changed as described in [`TableDiff`], views, indexes
+/// and triggers which differ are dropped and created again, as well as the indexes and triggers of
+/// rebuilt tables.
+pub struct DatabaseDiff<'a> {
+    old: &'a Database,
+    new: &'a Database,
+    renames: Vec<(Ident, Ident, Ident)>
+}
+
+impl<'a> DatabaseDiff<'a> {
+    fn new(old: &'a Database, new: &'a Database) -> Self {
+        Self { old, new, renames: Vec::new() }
+    }
+
+    /// Takes `new_col` for `col` renamed in the table, see [`TableDiff::rename_column`].
+    pub fn rename_column(&mut self, table: &str, col: &str, new_col: &str) -> &mut Self {
+        self.renames.push((Ident::new(table), Ident::new(col), Ident::new(new_col)));
+        self
+    }
+
+    /// The statements, none when the schemas are the same.
+    pub fn statements(&self) -> Vec<String> {
+        let tables = self.new.ordered_tables().into_iter()
+            .map(|t| (t, self.old.table(t.name()).map(|old| self.table_diff(old, t))))
+            .collect::<Vec<_>>();
+        let rebuilt = tables.iter()
+            .filter(|(_, diff)| diff.as_ref().is_some_and(|d| d.requires_rebuild()))
+            .map(|(t, _)| t.name())
+            .collect::<Vec<&str>>();
+        let is_rebuilt = |table: &Ident| rebuilt.iter().any(|t| t.eq_ignore_ascii_case(table.as_str()));
+
+        let old_views = self.old.ordered_views().into_iter().map(|v| v.to_string()).collect::<Vec<String>>();
+        let old_indexes = self.old.indexes().iter().map(|i| i.to_string()).collect::<Vec<String>>();
+        let old_triggers = self.old.triggers().iter().map(|t| t.to_string()).collect::<Vec<String>>();
+        let new_views = self.new.ordered_views().into_iter().map(|v| v.to_string()).collect::<Vec<String>>();
+        let new_indexes = self.new.indexes().iter().map(|i| i.to_string()).collect::<Vec<String>>();
+        let new_triggers = self.new.triggers().iter().map(|t| t.to_string()).collect::<Vec<String>>();
+
+        let mut statements = Vec::new();
+        for (trigger, sql) in self.old.triggers().iter().zip(&old_triggers) {
+            if !new_triggers.contains(sql) {
+                statements.push(drop_trigger(trigger.name()).to_string());
+            }
+        }
+        for (view, sql) in self.old.ordered_views().into_iter().zip(&old_views).rev() {
+            if !new_views.contains(sql) {
+                statements.push(drop_view(view.name()).to_string());
+            }
+        }
+        for (index, sql) in self.old.indexes().iter().zip(&old_indexes) {
+            if !new_indexes.contains(sql) {
+                statements.push(drop_index(index.name()).to_string());
+            }
+        }
+        for (table, diff) in &tables {
+            match diff {
+                Some(diff) => statements.extend(diff.statements()),
+                None => statements.push(table.to_string())
+            }
+        }
+        for table in self.old.ordered_tables().into_iter().rev() {
+            if self.new.table(table.name()).is_none() {
+                statements.push(drop_table(table.name()).to_string());
+            }
+        }
+        statements.extend(new_views.iter().filter(|sql| !old_views.contains(sql)).cloned());
+        for (index, sql) in self.new.indexes().iter().zip(new_indexes) {
+            if !old_indexes.contains(&sql) || is_rebuilt(index.table()) {
+                statements.push(sql);
+            }
+        }
+        for (trigger, sql) in self.new.triggers().iter().zip(new_triggers) {
+            if !old_triggers.contains(&sql) || is_rebuilt(trigger.table()) {
+                statements.push(sql);
+            }
+        }
+        statements
+    }
+
+    fn table_diff<'b>(&self, old: &'b Table, new: &'b Table) -> TableDiff<'b> {
+        let mut diff = TableDiff::new(old, new);
+        for (_, from, to) in self.renames.iter().filter(|r| r.0.as_str().eq_ignore_ascii_case(new.name())) {
+            diff.rename_column(from.as_str(), to.as_str());
+        }
+        diff
+    }
+}
+
+impl Statement for DatabaseDiff<'_> {
+    fn has_transaction(&self) -> bool {
+        self.new.tables().iter().any(|t| self.old.table(t.name()).is_some_and(|old| self.table_diff(old, t).requires_rebuild()))
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.old.validate()?;
+        self.new.validate()?;
+        for (table, _, _) in &self.renames {
+            match (self.old.table(table.as_str()), self.new.table(table.as_str())) {
+                (Some(old), Some(new)) => self.table_diff(old, new).validate()?,
+                _ => return Err(Error::UnknownTable { object: table.as_str().to_owned(), table: table.as_str().to_owned() })
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DatabaseDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.statements().join("\n"))
+    }
+}
+
+/// Rebuilds the table `old` as `new` following <https://www.sqlite.org/lang_altertable.html#otheralter>:
+/// `new` is created under a temporary name, the `(column, old column)` pairs of `copy` are copied,
+/// `old` is dropped and the new table renamed, in a transaction with foreign keys off.
+///
+/// `PRAGMA foreign_key_check` returns the violations of every table before the commit, including rows
+/// referencing the rebuilt table, and fails on foreign keys it no longer matches.
+/// [`Migrator`](crate::migrate::Migrator) rolls the rebuild back in both cases. Foreign keys are left off,
+/// the caller restores their previous setting as `Migrator` does.
+/// `legacy_alter_table` keeps the rename from failing on views of the table.
+pub(crate) fn rebuild(old: &str, new: &Table, copy: &[(&str, &str)]) -> Vec<String> {
+    let temp = Ident::new(&format!("new_{}", new.name()));
+    let mut statements = vec![
+        "PRAGMA foreign_keys = OFF;".to_owned(),
+        "BEGIN;".to_owned(),
+        new.create_sql(&temp, false)
+    ];
+    if !copy.is_empty() {
+        let columns = copy.iter().map(|c| c.0).collect::<Vec<&str>>();
+        let sources = copy.iter().map(|c| Ident::new(c.1).to_string()).collect::<Vec<String>>();
+        let sources = sources.iter().map(String::as_str).collect::<Vec<&str>>();
+        statements.push(insert(&columns).into(temp.as_str()).select(select(&sources).from(old)).to_string() + ";");
+    }
+    statements.push(drop_table(old).to_string());
+    statements.push("PRAGMA legacy_alter_table = ON;".to_owned());
+    statements.push(alter_table(temp.as_str()).rename_to(new.name()).to_string());
+    statements.push("PRAGMA legacy_alter_table = OFF;".to_owned());
+    statements.extend(new.indexes().iter().map(|i| i.to_string()));
+    statements.push("PRAGMA foreign_key_check;".to_owned());
+    statements.push("COMMIT;".to_owned());
+    statements
+}
+
+/// SQL with the given identifiers renamed, case-insensitively. String literals are left as they are.
+fn rename_identifiers(sql: &str, renames: &[(&str, &Ident)]) -> String {
+    map_identifiers(sql, |name| renames.iter().find(|r| r.0.eq_ignore_ascii_case(name)).map(|r| r.1.to_string()))
+}
+
+/// SQL with the identifiers and keywords unquoted and lowercased, to compare definitions case-insensitively.
+fn normalized(sql: &str) -> String {
+    map_identifiers(sql, |name| Some(name.to_ascii_lowercase()))
+}
+
+/// Whether the SQL uses the identifier, case-insensitively.
+pub(crate) fn mentions(sql: &str, identifier: &str) -> bool {
+    let mut found = false;
+    map_identifiers(sql, |name| {
+        found |= name.eq_ignore_ascii_case(identifier);
+        None
+    });
+    found
+}
+
+/// SQL with the identifiers, quoted or not, replaced by what `f` returns for their name, when it returns something.
+fn map_identifiers(sql: &str, mut f: impl FnMut(&str) -> Option<String>) -> String {
+    let chars = sql.chars().collect::<Vec<char>>();
+    let mut mapped = String::with_capacity(sql.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let name = match chars[i] {
+            '\'' => {
+                i = chars[i + 1..].iter().position(|&c| c == '\'').map_or(chars.len(), |p| i + p + 2);
+                None
+            },
+            open @ ('"' | '`' | '[') => {
+                let close = if open == '[' { ']' } else { open };
+                i = chars[i + 1..].iter().position(|&c| c == close).map_or(chars.len(), |p| i + p + 2);
+                Some(chars[start + 1..i - 1].iter().collect::<String>())
+            },
+            c if c.is_alphabetic() || c == '_' => {
+                i += chars[i..].iter().take_while(|c| c.is_alphanumeric() || **c == '_' || **c == '$').count();
+                Some(chars[start..i].iter().collect::<String>())
+            },
+            c if c.is_ascii_digit() => {
+                i += chars[i..].iter().take_while(|c| c.is_alphanumeric() || **c == '.').count();
+                None
+            },
+            _ => {
+                i += 1;
+                None
+            }
+        };
+        match name.and_then(|n| f(&n)) {
+            Some(replacement) => mapped.push_str(&replacement),
+            None => mapped.extend(&chars[start..i.min(chars.len())])
+        }
+    }
+    mapped
+}
+
+/// Compares two definitions of a table.
+pub fn diff_tables<'a>(old: &'a Table, new: &'a Table) -> TableDiff<'a> {
+    TableDiff::new(old, new)
+}
+
+/// Compares two schemas.
+pub fn diff_databases<'a>(old: &'a Database, new: &'a Database) -> DatabaseDiff<'a> {
+    DatabaseDiff::new(old, new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_tables, diff_databases, rename_identifiers, mentions};
+    use crate::schema::{create_table, create_index, create_trigger, create_view, Column, Database, Table};
+    use crate::{Ident, Statement, Value};
+
+    fn users() -> Table {
+        let mut users = create_table("users");
+        users.add_column(Column::new("id").primary_key())
+            .add_column(Column::new("email").text())
+            .add_column(Column::new("age").nullable())
+            .add_index(create_index("idx_users_email").column("email"));
+        users
+    }
+
+    #[test]
+    fn renamed_identifiers() {
+        let name = Ident::new("order");
+        let sql = rename_identifiers("CHECK(\"pos\" > 0 AND pos <> 'pos' AND pos2 > 1e5)", &[("POS", &name)]);
+        assert_eq!(sql, "CHECK(\"order\" > 0 AND \"order\" <> 'pos' AND pos2 > 1e5)");
+        assert!(mentions("lower([Email]) = 'x'", "email"));
+        assert!(!mentions("lower(email2) = 'email'", "email"));
+    }
+
+    #[test]
+    fn alter() {
+        let old = users();
+        assert!(diff_tables(&old, &old).statements().is_empty());
+
+        let mut new = create_table("users");
+        new.add_column(Column::new("id").primary_key())
+            .add_column(Column::new("mail").text())
+            .add_column(Column::new("nick").text().nullable())
+            .add_index(create_index("idx_users_mail").unique().column("mail"));
+        let mut diff = diff_tables(&old, &new);
+        diff.rename_column("email", "mail");
+        assert!(!diff.requires_rebuild());
+        assert_eq!(diff.try_to_sql().unwrap(), "DROP INDEX IF EXISTS idx_users_email;\n\
+            ALTER TABLE users RENAME COLUMN email TO mail;\n\
+            ALTER TABLE users ADD COLUMN nick TEXT;\n\
+            ALTER TABLE users DROP COLUMN age;\n\
+            CREATE UNIQUE INDEX idx_users_mail ON users (mail);");
+
+        let mut new = create_table("people");
+        new.add_column(Column::new("id").primary_key())
+            .add_column(Column::new("email").text())
+            .add_column(Column::new("age").nullable())
+            .add_column(Column::new("score").real().default_literal(0.0))
+            .add_index(create_index("idx_users_email").column("email"));
+        assert_eq!(diff_tables(&old, &new).to_string(), "ALTER TABLE users ADD COLUMN score REAL NOT NULL DEFAULT 0.0;\n\
+            ALTER TABLE users RENAME TO people;");
+    }
+
+    #[test]
+    fn rebuild() {
+        let old = users();
+        let mut new = create_table("users");
+        new.add_column(Column::new("id").primary_key())
+            .add_column(Column::new("email").text().collate("NOCASE"))
+            .add_column(Column::new("created").text().default_value("CURRENT_TIMESTAMP"))
+            .add_column(Column::new("years").nullable())
+            .add_index(create_index("idx_users_email").column("email"));
+        let mut diff = diff_tables(&old, &new);
+        diff.rename_column("age", "years");
+        assert!(diff.requires_rebuild());
+        assert_eq!(diff.to_string(), "PRAGMA foreign_keys = OFF;\n\
+            BEGIN;\n\
+            CREATE TABLE new_users (\n\
+            id INTEGER PRIMARY KEY NOT NULL,\n\
+            email TEXT NOT NULL COLLATE NOCASE,\n\
+            created TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,\n\
+            years INTEGER\n\
+            );\n\
+            INSERT INTO new_users(id, email, years) SELECT id, email, age FROM users;\n\
+            DROP TABLE users;\n\
+            PRAGMA legacy_alter_table = ON;\n\
+            ALTER TABLE new_users RENAME TO users;\n\
+            PRAGMA legacy_alter_table = OFF;\n\
+            CREATE INDEX idx_users_email ON users (email);\n\
+            PRAGMA foreign_key_check;\n\
+            COMMIT;");
+
+        let mut strict = users();
+        strict.strict();
+        assert!(diff_tables(&old, &strict).requires_rebuild());
+
+        let mut err = diff_tables(&old, &new);
+        err.rename_column("name", "years");
+        assert_eq!(err.try_to_sql().unwrap_err(), crate::Error::UnknownColumn { table: "users".into(), column: "name".into() });
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let mut old = create_table("users");
+        old.add_column(Column::new("id").declared_type("integer").primary_key())
+            .add_column(Column::new("email").declared_type("text").collate("nocase"))
+            .add_unique(&["EMAIL"]);
+        let mut new = create_table("users");
+        new.add_column(Column::new("id").primary_key())
+            .add_column(Column::new("email").text().collate("NOCASE"))
+            .add_unique(&["email"]);
+        let diff = diff_tables(&old, &new);
+        assert!(!diff.requires_rebuild());
+        assert_eq!(diff.to_string(), "");
+    }
+
+    #[test]
+    fn not_null_added() {
+        let old = users();
+        let mut new = users();
+        new.add_column(Column::new("nick").text());
+        assert!(diff_tables(&old, &new).requires_rebuild());
+
+        let mut new = users();
+        new.add_column(Column::new("nick").text().default_literal(Value::Null));
+        assert!(diff_tables(&old, &new).requires_rebuild());
+
+        let mut new = users();
+        new.add_column(Column::new("nick").text().default_literal("anon"));
+        assert_eq!(diff_tables(&old, &new).to_string(), "ALTER TABLE users ADD COLUMN nick TEXT NOT NULL DEFAULT 'anon';");
+    }
+
+    #[cfg(feature = "rusqlite")]
+    #[test]
+    fn sqlite() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let old = users();
+        conn.execute_batch(&old.to_string()).unwrap();
+        conn.execute_batch("INSERT INTO users(id, email) VALUES(1, 'pooh@hundredacreforest.org');").unwrap();
+
+        let mut new = users();
+        new.add_column(Column::new("nick").text().default_literal("anon"));
+        conn.execute_batch(&diff_tables(&old, &new).to_string()).unwrap();
+
+        let mut newer = new.clone();
+        newer.add_column(Column::new("visits"));
+        let diff = diff_tables(&new, &newer);
+        assert!(diff.requires_rebuild());
+        conn.execute_batch("DELETE FROM users;").unwrap();
+        conn.execute_batch(&diff.to_string()).unwrap();
+        let sql: String = conn.query_row("SELECT sql FROM sqlite_schema WHERE name = 'users'", [], |r| r.get(0)).unwrap();
+        assert!(sql.contains("visits INTEGER NOT NULL"));
+    }
+
+    #[test]
+    fn databases() {
+        let mut posts = create_table("posts");
+        posts.add_column(Column::new("id").primary_key()).add_column(Column::new("title").text());
+        let mut old = Database::new();
+        old.add_table(users())
+            .add_table(posts)
+            .add_view(create_view("adults").as_("SELECT * FROM users WHERE age >= 18"))
+            .add_trigger(create_trigger("users_insert").after().insert().on("users").statement("SELECT 1"));
+
+        let mut active = users();
+        active.add_column(Column::new("active").default_literal(true).check("active IN (0, 1)"));
+        let mut tags = create_table("tags");
+        tags.add_column(Column::new("name").text().primary_key());
+        let mut new = Database::new();
+        new.add_table(active)
+            .add_table(tags)
+            .add_view(create_view("adults").as_("SELECT * FROM users WHERE age >= 21"))
+            .add_index(create_index("idx_tags").on("tags").column("name"))
+            .add_trigger(create_trigger("users_insert").after().insert().on("users").statement("SELECT 1"));
+
+        assert!(diff_databases(&old, &old).statements().is_empty());
+        let diff = diff_databases(&old, &new);
+        assert_eq!(diff.try_to_sql().unwrap(), "DROP VIEW IF EXISTS adults;\n\
+            ALTER TABLE users ADD COLUMN active INTEGER NOT NULL CHECK(active IN (0, 1)) DEFAULT 1;\n\
+            CREATE TABLE IF NOT EXISTS tags (\nname TEXT PRIMARY KEY NOT NULL\n);\n\
+            DROP TABLE posts;\n\
+            CREATE VIEW IF NOT EXISTS adults AS SELECT * FROM users WHERE age >= 21;\n\
+            CREATE INDEX idx_tags ON tags (name);");
+
+        let mut unique = users();
+        unique.add_column(Column::new("active").default_literal(true).check("active IN (0, 1)"))
+            .add_unique(&["email"]);
+        let mut tags = create_table("tags");
+        tags.add_column(Column::new("name").text().primary_key());
+        let mut newer = Database::new();
+        newer.add_table(unique)
+            .add_table(tags)
+            .add_view(create_view("adults").as_("SELECT * FROM users WHERE age >= 21"))
+            .add_index(create_index("idx_tags").on("tags").column("name"))
+            .add_trigger(create_trigger("users_insert").after().insert().on("users").statement("SELECT 1"));
+        let sql = diff_databases(&new, &newer).to_string();
+        assert!(sql.starts_with("PRAGMA foreign_keys = OFF;\nBEGIN;\nCREATE TABLE new_users"));
+        assert!(sql.ends_with("COMMIT;\nCREATE TRIGGER IF NOT EXISTS users_insert AFTER INSERT ON users\nBEGIN\nSELECT 1;\nEND;"));
+    }
+}
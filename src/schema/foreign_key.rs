@@ -1,5 +1,8 @@
 use std::fmt;
 
+use crate::{Ident, Error};
+use crate::ident::{self, fmt_idents};
+
 #[derive(Debug, Clone, Copy)]
 pub enum ForeignKeyAction {
     SetNull,
@@ -11,7 +14,7 @@ pub enum ForeignKeyAction {
 
 impl ForeignKeyAction {
     fn as_str(&self) -> &str {
-        match self {            
+        match self {
             Self::SetNull => "SET NULL",
             Self::SetDefault => "SET DEFAULT",
             Self::Restrict => "RESTRICT",
@@ -27,30 +30,63 @@ impl fmt::Display for ForeignKeyAction {
     }
 }
 
-#[derive(Debug)]
-pub struct ForeignKey {
-    col: String,
-    ref_col: String,
-    ref_table: String,
-    delete_action: Option<ForeignKeyAction>,
-    update_action: Option<ForeignKeyAction>
+/// `MATCH` clause, SQLite parses it but always uses simple matching.
+#[derive(Debug, Clone, Copy)]
+pub enum ForeignKeyMatch {
+    Simple,
+    Partial,
+    Full
 }
 
-impl ForeignKey {
-    pub fn new(col: &str) -> Self {
-        Self {
-            col: col.to_owned(),
-            ref_col: String::new(),
-            ref_table: String::new(),
-            delete_action: Some(ForeignKeyAction::Restrict),
-            update_action: None
+impl fmt::Display for ForeignKeyMatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Simple => write!(f, "SIMPLE"),
+            Self::Partial => write!(f, "PARTIAL"),
+            Self::Full => write!(f, "FULL")
         }
     }
+}
 
-    pub fn references(mut self, table: &str, col: &str) -> Self {
-        self.ref_col = String::from(col);
-        self.ref_table = String::from(table);
-        self
+#[derive(Debug, Clone, Copy)]
+enum Deferral {
+    Deferrable,
+    NotDeferrable,
+    InitiallyDeferred,
+    InitiallyImmediate
+}
+
+impl fmt::Display for Deferral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Deferrable => write!(f, "DEFERRABLE"),
+            Self::NotDeferrable => write!(f, "NOT DEFERRABLE"),
+            Self::InitiallyDeferred => write!(f, "DEFERRABLE INITIALLY DEFERRED"),
+            Self::InitiallyImmediate => write!(f, "DEFERRABLE INITIALLY IMMEDIATE")
+        }
+    }
+}
+
+/// Foreign key clause, `REFERENCES table (columns)` followed by actions and deferral.
+/// Used by table-level [`ForeignKey`]s and by [`Column::references`](super::Column::references).
+#[derive(Debug, Clone, Default)]
+pub struct References {
+    table: Ident,
+    columns: Vec<Ident>,
+    delete_action: Option<ForeignKeyAction>,
+    update_action: Option<ForeignKeyAction>,
+    match_: Option<ForeignKeyMatch>,
+    deferrable: Option<Deferral>
+}
+
+impl References {
+    /// Referenced table and columns, with no columns the primary key of the table is referenced.
+    pub fn new(table: &str, columns: &[&str]) -> Self {
+        Self {
+            table: Ident::new(table),
+            columns: ident::idents(columns),
+            ..Self::default()
+        }
     }
 
     pub fn on_update(mut self, action: ForeignKeyAction) -> Self {
@@ -62,17 +98,217 @@ impl ForeignKey {
         self.delete_action = Some(action);
         self
     }
+
+    pub fn match_(mut self, match_: ForeignKeyMatch) -> Self {
+        self.match_ = Some(match_);
+        self
+    }
+
+    pub fn deferrable(mut self) -> Self {
+        self.deferrable = Some(Deferral::Deferrable);
+        self
+    }
+
+    pub fn not_deferrable(mut self) -> Self {
+        self.deferrable = Some(Deferral::NotDeferrable);
+        self
+    }
+
+    /// `DEFERRABLE INITIALLY DEFERRED`, the constraint is checked when the transaction commits.
+    pub fn initially_deferred(mut self) -> Self {
+        self.deferrable = Some(Deferral::InitiallyDeferred);
+        self
+    }
+
+    pub fn initially_immediate(mut self) -> Self {
+        self.deferrable = Some(Deferral::InitiallyImmediate);
+        self
+    }
+
+    pub(crate) fn table(&self) -> &Ident {
+        &self.table
+    }
+
+    pub(crate) fn columns(&self) -> &[Ident] {
+        &self.columns
+    }
+
+    /// Checks the clause used for the child columns `cols`.
+    pub(crate) fn validate(&self, cols: &[Ident]) -> Result<(), Error> {
+        let column = || cols.iter().map(|c| c.as_str()).collect::<Vec<&str>>().join(", ");
+        if self.table.as_str().is_empty() {
+            return Err(Error::MissingReference { column: column() });
+        }
+        if !self.columns.is_empty() && self.columns.len() != cols.len() {
+            return Err(Error::ReferenceColumnCount { column: column(), expected: cols.len(), found: self.columns.len() });
+        }
+        Ok(())
+    }
 }
 
-impl fmt::Display for ForeignKey {
+impl fmt::Display for References {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, " FOREIGN KEY ({}) REFERENCES {} ({}) ", self.col, self.ref_table, self.ref_col)?;
+        write!(f, "REFERENCES {}", self.table)?;
+        if !self.columns.is_empty() {
+            write!(f, " ({})", fmt_idents(&self.columns))?;
+        }
         if let Some(del) = self.delete_action {
-            write!(f, "ON DELETE {} ", del)?;
+            write!(f, " ON DELETE {}", del)?;
         }
         if let Some(upd) = self.update_action {
-            write!(f, "ON UPDATE {} ", upd)?;
+            write!(f, " ON UPDATE {}", upd)?;
+        }
+        if let Some(m) = self.match_ {
+            write!(f, " MATCH {}", m)?;
+        }
+        if let Some(d) = self.deferrable {
+            write!(f, " {}", d)?;
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone)]
+pub struct ForeignKey {
+    name: Option<Ident>,
+    cols: Vec<Ident>,
+    references: References
+}
+
+impl ForeignKey {
+    pub fn new(col: &str) -> Self {
+        Self::with_columns(&[col])
+    }
+
+    /// Composite foreign key, `FOREIGN KEY (a, b)`.
+    pub fn with_columns(cols: &[&str]) -> Self {
+        Self {
+            name: None,
+            cols: ident::idents(cols),
+            references: References::default()
+        }
+    }
+
+    /// Names the constraint, `CONSTRAINT name FOREIGN KEY ...`.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(Ident::new(name));
+        self
+    }
+
+    pub fn references(self, table: &str, col: &str) -> Self {
+        self.references_columns(table, &[col])
+    }
+
+    /// Referenced table and columns, with no columns the primary key of the table is referenced.
+    pub fn references_columns(mut self, table: &str, cols: &[&str]) -> Self {
+        self.references.table = Ident::new(table);
+        self.references.columns = ident::idents(cols);
+        self
+    }
+
+    /// Referenced table given as a clause, as parsed from SQL.
+    #[cfg(feature = "parse")]
+    pub(crate) fn with_references(mut self, references: References) -> Self {
+        self.references = references;
+        self
+    }
+
+    pub fn on_update(mut self, action: ForeignKeyAction) -> Self {
+        self.references = self.references.on_update(action);
+        self
+    }
+
+    pub fn on_delete(mut self, action: ForeignKeyAction) -> Self {
+        self.references = self.references.on_delete(action);
+        self
+    }
+
+    pub fn match_(mut self, match_: ForeignKeyMatch) -> Self {
+        self.references = self.references.match_(match_);
+        self
+    }
+
+    pub fn deferrable(mut self) -> Self {
+        self.references = self.references.deferrable();
+        self
+    }
+
+    pub fn not_deferrable(mut self) -> Self {
+        self.references = self.references.not_deferrable();
+        self
+    }
+
+    /// `DEFERRABLE INITIALLY DEFERRED`, the constraint is checked when the transaction commits.
+    pub fn initially_deferred(mut self) -> Self {
+        self.references = self.references.initially_deferred();
+        self
+    }
+
+    pub fn initially_immediate(mut self) -> Self {
+        self.references = self.references.initially_immediate();
+        self
+    }
+
+    pub(crate) fn columns(&self) -> &[Ident] {
+        &self.cols
+    }
+
+    pub(crate) fn references_clause(&self) -> &References {
+        &self.references
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        self.references.validate(&self.cols)
+    }
+}
+
+impl fmt::Display for ForeignKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref n) = self.name {
+            write!(f, "CONSTRAINT {} ", n)?;
+        }
+        write!(f, "FOREIGN KEY ({}) {}", fmt_idents(&self.cols), self.references)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ForeignKey, ForeignKeyAction, ForeignKeyMatch, References};
+    use crate::Error;
+
+    #[test]
+    fn single() {
+        let fk = ForeignKey::new("deptId").references("depts", "id");
+        assert_eq!(fk.to_string(), "FOREIGN KEY (deptId) REFERENCES depts (id)");
+
+        let fk = ForeignKey::new("deptId").references("depts", "id").on_delete(ForeignKeyAction::Cascade).on_update(ForeignKeyAction::SetNull);
+        assert_eq!(fk.to_string(), "FOREIGN KEY (deptId) REFERENCES depts (id) ON DELETE CASCADE ON UPDATE SET NULL");
+    }
+
+    #[test]
+    fn composite_named_deferred() {
+        let fk = ForeignKey::with_columns(&["songArtist", "songAlbum"])
+            .name("fk_song_album")
+            .references_columns("album", &["albumArtist", "albumName"])
+            .on_delete(ForeignKeyAction::Restrict)
+            .match_(ForeignKeyMatch::Full)
+            .initially_deferred();
+        assert_eq!(fk.to_string(), "CONSTRAINT fk_song_album FOREIGN KEY (songArtist, songAlbum) REFERENCES album (albumArtist, albumName) ON DELETE RESTRICT MATCH FULL DEFERRABLE INITIALLY DEFERRED");
+    }
+
+    #[test]
+    fn references() {
+        assert_eq!(References::new("users", &[]).not_deferrable().to_string(), "REFERENCES users NOT DEFERRABLE");
+        assert_eq!(References::new("users", &["id"]).deferrable().to_string(), "REFERENCES users (id) DEFERRABLE");
+        assert_eq!(References::new("users", &["id"]).initially_immediate().to_string(), "REFERENCES users (id) DEFERRABLE INITIALLY IMMEDIATE");
+    }
+
+    #[test]
+    fn validate() {
+        assert_eq!(ForeignKey::with_columns(&["a", "b"]).validate(), Err(Error::MissingReference { column: "a, b".into() }));
+        let err = ForeignKey::with_columns(&["a", "b"]).references("t", "x").validate().unwrap_err();
+        assert_eq!(err, Error::ReferenceColumnCount { column: "a, b".into(), expected: 2, found: 1 });
+        assert_eq!(err.to_string(), "foreign key on a, b references 1 columns, expected 2");
+        assert_eq!(ForeignKey::with_columns(&["a", "b"]).references_columns("t", &[]).validate(), Ok(()));
+    }
+}
@@ -0,0 +1,273 @@
+use std::fmt;
+
+use crate::expr::Expr;
+use crate::{Ident, Statement, Error};
+use crate::order::SortOrder;
+use super::diff::mentions;
+
+#[derive(Debug, Clone)]
+enum IndexTarget {
+    Column(Ident),
+    Expr(Expr)
+}
+
+/// Column or expression of an index, with optional collation and sort order.
+#[derive(Debug, Clone)]
+pub struct IndexedColumn {
+    target: IndexTarget,
+    collation: Option<Ident>,
+    order: Option<SortOrder>
+}
+
+impl IndexedColumn {
+    pub fn new(name: &str) -> Self {
+        Self {
+            target: IndexTarget::Column(Ident::new(name)),
+            collation: None,
+            order: None
+        }
+    }
+
+    pub fn expr(expr: impl Into<Expr>) -> Self {
+        Self {
+            target: IndexTarget::Expr(expr.into()),
+            collation: None,
+            order: None
+        }
+    }
+
+    pub fn collate(mut self, collation: &str) -> Self {
+        self.collation = Some(Ident::new(collation));
+        self
+    }
+
+    pub fn asc(mut self) -> Self {
+        self.order = Some(SortOrder::Asc);
+        self
+    }
+
+    pub fn desc(mut self) -> Self {
+        self.order = Some(SortOrder::Desc);
+        self
+    }
+}
+
+impl From<&str> for IndexedColumn {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl fmt::Display for IndexedColumn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.target {
+            IndexTarget::Column(ref c) => write!(f, "{}", c)?,
+            IndexTarget::Expr(ref e) if e.is_atomic() => write!(f, "{}", e)?,
+            IndexTarget::Expr(ref e) => write!(f, "({})", e)?
+        }
+        if let Some(ref c) = self.collation {
+            write!(f, " COLLATE {}", c)?;
+        }
+        if let Some(o) = self.order {
+            write!(f, " {}", o)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Index {
+    name: Ident,
+    table: Ident,
+    unique: bool,
+    if_not_exists: bool,
+    columns: Vec<IndexedColumn>,
+    where_: Option<Expr>
+}
+
+impl Index {
+    fn new(name: &str) -> Self {
+        Self {
+            name: Ident::new(name),
+            table: Ident::default(),
+            unique: false,
+            if_not_exists: false,
+            columns: Vec::new(),
+            where_: None
+        }
+    }
+
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    pub fn if_not_exists(mut self) -> Self {
+        self.if_not_exists = true;
+        self
+    }
+
+    /// Table of the index, it is set automatically when the index is added with [`Table::add_index`](super::Table::add_index).
+    pub fn on(mut self, table: &str) -> Self {
+        self.table = Ident::new(table);
+        self
+    }
+
+    pub fn column(mut self, col: impl Into<IndexedColumn>) -> Self {
+        self.columns.push(col.into());
+        self
+    }
+
+    pub fn columns(mut self, cols: &[&str]) -> Self {
+        self.columns.extend(cols.iter().map(|c| IndexedColumn::new(c)));
+        self
+    }
+
+    /// Makes the index partial, only rows matching the condition are indexed.
+    pub fn where_(mut self, cond: impl Into<Expr>) -> Self {
+        self.where_ = Some(cond.into());
+        self
+    }
+
+    pub(super) fn set_table(&mut self, table: &Ident) {
+        self.table = table.clone();
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub(crate) fn table(&self) -> &Ident {
+        &self.table
+    }
+
+    /// Names of the indexed columns, expressions are skipped.
+    pub(crate) fn column_names(&self) -> Vec<&str> {
+        self.columns.iter().filter_map(|c| match c.target {
+            IndexTarget::Column(ref name) => Some(name.as_str()),
+            IndexTarget::Expr(_) => None
+        }).collect()
+    }
+
+    /// Whether the column is indexed or used by an indexed expression or the condition of the index.
+    pub(crate) fn uses_column(&self, name: &str) -> bool {
+        self.columns.iter().any(|c| mentions(&c.to_string(), name)) || self.where_.as_ref().is_some_and(|w| mentions(&w.to_string(), name))
+    }
+
+    /// Whether the index makes its columns a unique key, ie. it is unique, not partial and has no expressions.
+    pub(crate) fn is_unique_key(&self) -> bool {
+        self.unique && self.where_.is_none() && self.columns.iter().all(|c| matches!(c.target, IndexTarget::Column(_)))
+    }
+}
+
+impl Statement for Index {
+    fn validate(&self) -> Result<(), Error> {
+        if self.columns.is_empty() {
+            return Err(Error::NoIndexedColumns { index: self.name.as_str().to_owned() });
+        }
+        if self.table.as_str().is_empty() {
+            return Err(Error::MissingIndexTable { index: self.name.as_str().to_owned() });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Index {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CREATE ")?;
+        if self.unique {
+            write!(f, "UNIQUE ")?;
+        }
+        write!(f, "INDEX ")?;
+        if self.if_not_exists {
+            write!(f, "IF NOT EXISTS ")?;
+        }
+        let columns = self.columns.iter().map(|it| it.to_string()).collect::<Vec<String>>().join(", ");
+        write!(f, "{} ON {} ({})", self.name, self.table, columns)?;
+        if let Some(ref w) = self.where_ {
+            write!(f, " WHERE {}", w)?;
+        }
+        write!(f, ";")
+    }
+}
+
+#[derive(Debug)]
+pub struct IndexDrop(Ident);
+
+impl Statement for IndexDrop {}
+
+impl fmt::Display for IndexDrop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DROP INDEX IF EXISTS {};", self.0)
+    }
+}
+
+pub fn create_index(name: &str) -> Index {
+    Index::new(name)
+}
+
+pub fn drop_index(name: &str) -> IndexDrop {
+    IndexDrop(Ident::new(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create_index, drop_index, IndexedColumn};
+    use crate::expr::{col, func, lit};
+    use crate::{Statement, Error};
+
+    #[test]
+    fn create() {
+        let idx = create_index("idx_users_email").on("users").column("email");
+        assert_eq!(idx.to_string(), "CREATE INDEX idx_users_email ON users (email);");
+    }
+
+    #[test]
+    fn create_unique_if_not_exists() {
+        let idx = create_index("idx_users_email").unique().if_not_exists().on("users").columns(&["tenant_id", "email"]);
+        assert_eq!(idx.to_string(), "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_email ON users (tenant_id, email);");
+    }
+
+    #[test]
+    fn create_ordered_collated() {
+        let idx = create_index("idx_people_name")
+            .on("people")
+            .column(IndexedColumn::new("lastname").collate("NOCASE").asc())
+            .column(IndexedColumn::new("order").desc());
+        assert_eq!(idx.to_string(), "CREATE INDEX idx_people_name ON people (lastname COLLATE NOCASE ASC, \"order\" DESC);");
+    }
+
+    #[test]
+    fn create_expression() {
+        let idx = create_index("idx_lower_email")
+            .on("users")
+            .column(IndexedColumn::expr(func("lower", [col("email")])))
+            .column(IndexedColumn::expr(col("a") + col("b")).desc());
+        assert_eq!(idx.to_string(), "CREATE INDEX idx_lower_email ON users (lower(email), (a + b) DESC);");
+
+        let idx = create_index("idx_names").on("users").column(IndexedColumn::expr("first || last").collate("NOCASE"));
+        assert_eq!(idx.to_string(), "CREATE INDEX idx_names ON users ((first || last) COLLATE NOCASE);");
+    }
+
+    #[test]
+    fn create_partial() {
+        let idx = create_index("idx_active_users")
+            .on("users")
+            .column("login")
+            .where_(col("active").eq(lit(1)).and(col("deleted").is_null()));
+        assert_eq!(idx.to_string(), "CREATE INDEX idx_active_users ON users (login) WHERE active = 1 AND deleted IS NULL;");
+    }
+
+    #[test]
+    fn invalid() {
+        let err = create_index("idx_email").column("email").try_to_sql().unwrap_err();
+        assert_eq!(err, Error::MissingIndexTable { index: "idx_email".into() });
+        let err = create_index("idx_email").on("users").try_to_sql().unwrap_err();
+        assert_eq!(err, Error::NoIndexedColumns { index: "idx_email".into() });
+    }
+
+    #[test]
+    fn drop_() {
+        assert_eq!(drop_index("idx_users_email").to_string(), "DROP INDEX IF EXISTS idx_users_email;");
+    }
+}
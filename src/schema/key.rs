@@ -0,0 +1,114 @@
+use std::fmt;
+
+use crate::{Ident, ConflictResolution};
+use crate::ident::{self, fmt_idents};
+
+#[derive(Debug, Clone)]
+struct KeyConstraint {
+    name: Option<Ident>,
+    columns: Vec<Ident>,
+    conflict: Option<ConflictResolution>
+}
+
+impl KeyConstraint {
+    fn new(columns: &[&str]) -> Self {
+        Self {
+            name: None,
+            columns: ident::idents(columns),
+            conflict: None
+        }
+    }
+
+    fn fmt(&self, f: &mut fmt::Formatter, keyword: &str) -> fmt::Result {
+        if let Some(ref n) = self.name {
+            write!(f, "CONSTRAINT {} ", n)?;
+        }
+        write!(f, "{} ({})", keyword, fmt_idents(&self.columns))?;
+        if let Some(c) = self.conflict {
+            write!(f, " ON CONFLICT {}", c)?;
+        }
+        Ok(())
+    }
+}
+
+/// Table-level `PRIMARY KEY` constraint, see [`Table::set_primary_key`](super::Table::set_primary_key).
+#[derive(Debug, Clone)]
+pub struct PrimaryKey(KeyConstraint);
+
+impl PrimaryKey {
+    pub fn new(columns: &[&str]) -> Self {
+        Self(KeyConstraint::new(columns))
+    }
+
+    /// Names the constraint, `CONSTRAINT name PRIMARY KEY (...)`.
+    pub fn name(mut self, name: &str) -> Self {
+        self.0.name = Some(Ident::new(name));
+        self
+    }
+
+    pub fn on_conflict(mut self, resolution: ConflictResolution) -> Self {
+        self.0.conflict = Some(resolution);
+        self
+    }
+
+    pub(super) fn columns(&self) -> &[Ident] {
+        &self.0.columns
+    }
+}
+
+impl fmt::Display for PrimaryKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f, "PRIMARY KEY")
+    }
+}
+
+/// Table-level `UNIQUE` constraint, see [`Table::add_unique_constraint`](super::Table::add_unique_constraint).
+#[derive(Debug, Clone)]
+pub struct Unique(KeyConstraint);
+
+impl Unique {
+    pub fn new(columns: &[&str]) -> Self {
+        Self(KeyConstraint::new(columns))
+    }
+
+    /// Names the constraint, `CONSTRAINT name UNIQUE (...)`.
+    pub fn name(mut self, name: &str) -> Self {
+        self.0.name = Some(Ident::new(name));
+        self
+    }
+
+    pub fn on_conflict(mut self, resolution: ConflictResolution) -> Self {
+        self.0.conflict = Some(resolution);
+        self
+    }
+
+    pub(super) fn columns(&self) -> &[Ident] {
+        &self.0.columns
+    }
+}
+
+impl fmt::Display for Unique {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f, "UNIQUE")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PrimaryKey, Unique};
+    use crate::ConflictResolution;
+
+    #[test]
+    fn primary_key() {
+        assert_eq!(PrimaryKey::new(&["userId", "groupId"]).to_string(), "PRIMARY KEY (userId, groupId)");
+        let pk = PrimaryKey::new(&["a", "order"]).name("pk_a").on_conflict(ConflictResolution::Replace);
+        assert_eq!(pk.to_string(), "CONSTRAINT pk_a PRIMARY KEY (a, \"order\") ON CONFLICT REPLACE");
+    }
+
+    #[test]
+    fn unique() {
+        assert_eq!(Unique::new(&["tenant_id", "email"]).to_string(), "UNIQUE (tenant_id, email)");
+        let u = Unique::new(&["email"]).name("uq_email").on_conflict(ConflictResolution::Ignore);
+        assert_eq!(u.to_string(), "CONSTRAINT uq_email UNIQUE (email) ON CONFLICT IGNORE");
+    }
+}
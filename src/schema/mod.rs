@@ -4,10 +4,26 @@ mod check;
 mod foreign_key;
 mod view;
 mod triggers;
+mod index;
+mod key;
+mod sql_type;
+mod database;
+mod diff;
 
-pub use column::Column;
+pub use column::{Column, GeneratedColumnType};
 pub use check::Check;
-pub use foreign_key::{ForeignKey, ForeignKeyAction};
-pub use table::{Table, create_table, drop_table, alter_table};
+pub use foreign_key::{ForeignKey, ForeignKeyAction, ForeignKeyMatch, References};
+pub use table::{Table, TableDefinition, create_table, drop_table, alter_table};
 pub use view::{create_view, drop_view};
-pub use triggers::{create_trigger, drop_trigger};
\ No newline at end of file
+#[cfg(feature = "parse")]
+pub(crate) use view::ViewCreateStmt;
+pub use triggers::{create_trigger, drop_trigger};
+#[cfg(feature = "parse")]
+pub(crate) use triggers::TriggerFull;
+pub use sql_type::SqlType;
+#[cfg(feature = "derive")]
+pub use yaqub_derive::Table;
+pub use key::{PrimaryKey, Unique};
+pub use index::{Index, IndexedColumn, create_index, drop_index};
+pub use database::Database;
+pub use diff::{TableDiff, DatabaseDiff, diff_tables, diff_databases};
\ No newline at end of file
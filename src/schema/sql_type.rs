@@ -0,0 +1,124 @@
+use crate::expr::{self, Expr};
+
+/// Mapping of a Rust type to an SQLite column, used by [`Column::of`](super::Column::of).
+///
+/// User types, eg. enums stored as text, implement it by hand:
+///
+/// ```
+/// use yaqub::expr::{col, lit, Expr};
+/// use yaqub::schema::{Column, SqlType};
+///
+/// enum Status { Active, Banned }
+///
+/// impl SqlType for Status {
+///     const TYPE: &'static str = "TEXT";
+///
+///     fn check(column: &str) -> Option<Expr> {
+///         Some(col(column).in_list([lit("active"), lit("banned")]))
+///     }
+/// }
+///
+/// let c = Column::of::<Option<Status>>("status");
+/// assert_eq!(c.to_string(), "status TEXT CHECK(status IN ('active', 'banned'))");
+/// ```
+pub trait SqlType {
+    /// Declared type of the column, eg. `INTEGER` or `VARCHAR(255)`.
+    const TYPE: &'static str;
+    /// Whether the column accepts `NULL`, only `Option<T>` does by default.
+    const NULLABLE: bool = false;
+
+    /// Constraint the values of the column named `column` have to satisfy.
+    fn check(_column: &str) -> Option<Expr> {
+        None
+    }
+}
+
+impl<T> SqlType for Option<T> where T: SqlType {
+    const TYPE: &'static str = T::TYPE;
+    const NULLABLE: bool = true;
+
+    fn check(column: &str) -> Option<Expr> {
+        T::check(column)
+    }
+}
+
+macro_rules! implement_sql_type {
+    ($typ: literal, $($t: ty),+) => {
+        $(
+            impl SqlType for $t {
+                const TYPE: &'static str = $typ;
+            }
+        )+
+    }
+}
+
+implement_sql_type!("INTEGER", i64, i32, i16, i8, u32, u16, u8);
+implement_sql_type!("REAL", f64, f32);
+implement_sql_type!("TEXT", String);
+implement_sql_type!("BLOB", Vec<u8>);
+
+/// Stored as `0` or `1`.
+impl SqlType for bool {
+    const TYPE: &'static str = "INTEGER";
+
+    fn check(column: &str) -> Option<Expr> {
+        Some(expr::col(column).in_list([expr::lit(0), expr::lit(1)]))
+    }
+}
+
+// Dates and times are stored as ISO-8601 text.
+#[cfg(feature = "chrono")]
+implement_sql_type!("TEXT", chrono::NaiveDate, chrono::NaiveTime, chrono::NaiveDateTime, chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::FixedOffset>);
+
+// Dates and times are stored as ISO-8601 text.
+#[cfg(feature = "time")]
+implement_sql_type!("TEXT", time::Date, time::Time, time::PrimitiveDateTime, time::OffsetDateTime);
+
+/// Stored as a 16 byte blob.
+#[cfg(feature = "uuid")]
+impl SqlType for uuid::Uuid {
+    const TYPE: &'static str = "BLOB";
+
+    fn check(column: &str) -> Option<Expr> {
+        Some(expr::func("length", [expr::col(column)]).eq(16))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::Column;
+
+    #[test]
+    fn primitives() {
+        assert_eq!(Column::of::<i64>("id").to_string(), "id INTEGER NOT NULL");
+        assert_eq!(Column::of::<f64>("price").to_string(), "price REAL NOT NULL");
+        assert_eq!(Column::of::<String>("name").to_string(), "name TEXT NOT NULL");
+        assert_eq!(Column::of::<Vec<u8>>("data").to_string(), "data BLOB NOT NULL");
+        assert_eq!(Column::of::<Option<String>>("nick").to_string(), "nick TEXT");
+    }
+
+    #[test]
+    fn bool_check() {
+        assert_eq!(Column::of::<bool>("active").to_string(), "active INTEGER NOT NULL CHECK(active IN (0, 1))");
+        assert_eq!(Column::of::<Option<bool>>("active").to_string(), "active INTEGER CHECK(active IN (0, 1))");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_types() {
+        assert_eq!(Column::of::<chrono::NaiveDate>("born").to_string(), "born TEXT NOT NULL");
+        assert_eq!(Column::of::<Option<chrono::DateTime<chrono::Utc>>>("seen").to_string(), "seen TEXT");
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_types() {
+        assert_eq!(Column::of::<time::OffsetDateTime>("created").to_string(), "created TEXT NOT NULL");
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid_type() {
+        assert_eq!(Column::of::<uuid::Uuid>("id").to_string(), "id BLOB NOT NULL CHECK(length(id) = 16)");
+    }
+}
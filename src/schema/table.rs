@@ -1,21 +1,35 @@
 use std::fmt;
 
-use super::{Column, Check, ForeignKey};
+use super::{Column, Check, ForeignKey, Index, PrimaryKey, References, Unique, diff_tables};
+use super::column::STRICT_TYPES;
+use super::diff::mentions;
+use crate::{Ident, Statement, Error};
 
+#[derive(Debug, Clone)]
 pub struct Table {    
-    name: String,
+    name: Ident,
     cols: Vec<Column>,
+    primary_key: Option<PrimaryKey>,
+    uniques: Vec<Unique>,
     checks: Vec<Check>,
-    foreign_keys: Vec<ForeignKey>
+    foreign_keys: Vec<ForeignKey>,
+    indexes: Vec<Index>,
+    strict: bool,
+    without_rowid: bool
 }
 
 impl Table {
     fn new(name: &str) -> Self {
         Self {           
-            name: name.to_owned(),
+            name: Ident::new(name),
             cols: Vec::new(),
+            primary_key: None,
+            uniques: Vec::new(),
             checks: Vec::new(),
-            foreign_keys: Vec::new()
+            foreign_keys: Vec::new(),
+            indexes: Vec::new(),
+            strict: false,
+            without_rowid: false
         }
     }
 
@@ -24,6 +38,28 @@ impl Table {
         self
     }
 
+    /// Composite primary key, `PRIMARY KEY (a, b)`. It replaces the previously set one.
+    pub fn primary_key(&mut self, columns: &[&str]) -> &mut Self {
+        self.set_primary_key(PrimaryKey::new(columns))
+    }
+
+    /// Primary key constraint which can be named or have a conflict clause.
+    pub fn set_primary_key(&mut self, key: PrimaryKey) -> &mut Self {
+        self.primary_key = Some(key);
+        self
+    }
+
+    /// `UNIQUE (a, b)` table constraint.
+    pub fn add_unique(&mut self, columns: &[&str]) -> &mut Self {
+        self.add_unique_constraint(Unique::new(columns))
+    }
+
+    /// Unique constraint which can be named or have a conflict clause.
+    pub fn add_unique_constraint(&mut self, unique: Unique) -> &mut Self {
+        self.uniques.push(unique);
+        self
+    }
+
     pub fn add_check(&mut self, check: Check) -> &mut Self {
         self.checks.push(check);
         self
@@ -33,45 +69,247 @@ impl Table {
         self.foreign_keys.push(foreign_key);
         self
     }
+
+    /// `STRICT` table, column types are enforced and limited to `INT`, `INTEGER`, `REAL`, `TEXT`, `BLOB` and `ANY`.
+    pub fn strict(&mut self) -> &mut Self {
+        self.strict = true;
+        self
+    }
+
+    /// `WITHOUT ROWID` table, it requires a primary key.
+    pub fn without_rowid(&mut self) -> &mut Self {
+        self.without_rowid = true;
+        self
+    }
+
+    /// Adds an index created right after the table, the index is put on this table.
+    pub fn add_index(&mut self, mut index: Index) -> &mut Self {
+        index.set_table(&self.name);
+        self.indexes.push(index);
+        self
+    }
+
+    /// `ALTER TABLE` of this table. Knowing the current definition, it also changes what SQLite cannot
+    /// alter directly, by rebuilding the table, as described on `AlterTable`.
+    pub fn alter(&self) -> AlterTable {
+        AlterTable {
+            definition: Some(Box::new(self.clone())),
+            ..AlterTable::new(self.name.as_str())
+        }
+    }
 }
 
-impl fmt::Display for Table {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl Table {
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub(crate) fn columns(&self) -> &[Column] {
+        &self.cols
+    }
+
+    pub(crate) fn column(&self, name: &str) -> Option<&Column> {
+        self.cols.iter().find(|c| c.name().eq_ignore_ascii_case(name))
+    }
+
+    pub(crate) fn indexes(&self) -> &[Index] {
+        &self.indexes
+    }
+
+    /// Child columns and clause of every foreign key, column-level ones included.
+    pub(crate) fn references(&self) -> Vec<(Vec<&str>, &References)> {
+        let columns = self.cols.iter().filter_map(|c| c.references_clause().map(|r| (vec![c.name()], r)));
+        let table = self.foreign_keys.iter().map(|fk| (fk.columns().iter().map(Ident::as_str).collect(), fk.references_clause()));
+        columns.chain(table).collect()
+    }
+
+    /// Columns of the primary key, empty when the table has none.
+    pub(crate) fn primary_key_columns(&self) -> Vec<&str> {
+        match self.primary_key {
+            Some(ref pk) => pk.columns().iter().map(Ident::as_str).collect(),
+            None => self.cols.iter().filter(|c| c.is_primary_key()).map(|c| c.name()).collect()
+        }
+    }
+
+    /// Column sets with a uniqueness constraint, from `UNIQUE` columns and constraints and unique indexes.
+    pub(crate) fn unique_keys(&self) -> Vec<Vec<&str>> {
+        let columns = self.cols.iter().filter(|c| c.is_unique()).map(|c| vec![c.name()]);
+        let constraints = self.uniques.iter().map(|u| u.columns().iter().map(Ident::as_str).collect());
+        let indexes = self.indexes.iter().filter(|i| i.is_unique_key()).map(|i| i.column_names());
+        columns.chain(constraints).chain(indexes).collect()
+    }
+
+    fn column_mut(&mut self, name: &str) -> Option<&mut Column> {
+        self.cols.iter_mut().find(|c| c.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Removes the column, with the table constraints and indexes involving it.
+    fn remove_column(&mut self, name: &str) {
+        let involves = |columns: &[Ident]| columns.iter().any(|c| c.as_str().eq_ignore_ascii_case(name));
+        self.cols.retain(|c| !c.name().eq_ignore_ascii_case(name));
+        if self.primary_key.as_ref().is_some_and(|pk| involves(pk.columns())) {
+            self.primary_key = None;
+        }
+        self.uniques.retain(|u| !involves(u.columns()));
+        self.checks.retain(|c| !mentions(&c.to_string(), name));
+        self.foreign_keys.retain(|fk| !involves(fk.columns()));
+        self.indexes.retain(|i| !i.uses_column(name));
+    }
+
+    fn validate_key_columns(&self, columns: &[Ident]) -> Result<(), Error> {
+        if columns.is_empty() {
+            return Err(Error::NoConstraintColumns { table: self.name.as_str().to_owned() });
+        }
+        match columns.iter().find(|k| self.column(k.as_str()).is_none()) {
+            Some(k) => Err(Error::UnknownColumn { table: self.name.as_str().to_owned(), column: k.as_str().to_owned() }),
+            None => Ok(())
+        }
+    }
+}
+
+impl Statement for Table {
+    fn validate(&self) -> Result<(), Error> {
+        if self.cols.is_empty() {
+            return Err(Error::NoColumns { table: self.name.as_str().to_owned() });
+        }
+        for (i, col) in self.cols.iter().enumerate() {
+            col.validate()?;
+            if self.cols[..i].iter().any(|c| c.name().eq_ignore_ascii_case(col.name())) {
+                return Err(Error::DuplicateColumn { table: self.name.as_str().to_owned(), column: col.name().to_owned() });
+            }
+        }
+        if let Some(ref pk) = self.primary_key {
+            if self.cols.iter().any(|c| c.is_primary_key()) {
+                return Err(Error::MultiplePrimaryKeys { table: self.name.as_str().to_owned() });
+            }
+            self.validate_key_columns(pk.columns())?;
+            if let Some(col) = self.cols.iter().find(|c| c.is_generated() && pk.columns().iter().any(|k| k.as_str().eq_ignore_ascii_case(c.name()))) {
+                return Err(Error::GeneratedPrimaryKey { column: col.name().to_owned() });
+            }
+        }
+        if self.without_rowid {
+            if self.primary_key.is_none() && !self.cols.iter().any(|c| c.is_primary_key()) {
+                return Err(Error::MissingPrimaryKey { table: self.name.as_str().to_owned() });
+            }
+            if self.cols.iter().any(|c| c.is_autoincrement()) {
+                return Err(Error::AutoincrementWithoutRowid { table: self.name.as_str().to_owned() });
+            }
+        }
+        if self.strict {
+            if let Some(col) = self.cols.iter().find(|c| !STRICT_TYPES.iter().any(|t| t.eq_ignore_ascii_case(c.typ()))) {
+                return Err(Error::StrictType { table: self.name.as_str().to_owned(), column: col.name().to_owned(), typ: col.typ().to_owned() });
+            }
+        }
+        self.uniques.iter().try_for_each(|u| self.validate_key_columns(u.columns()))?;
+        for fk in &self.foreign_keys {
+            fk.validate()?;
+            self.validate_key_columns(fk.columns())?;
+        }
+        self.indexes.iter().try_for_each(|index| index.validate())
+    }
+}
+
+impl Table {
+    /// `CREATE TABLE` statement of the table under the given name, without the indexes.
+    pub(crate) fn create_sql(&self, name: &Ident, if_not_exists: bool) -> String {
         const SEP: &str = ",\n";
-        writeln!(f, "CREATE TABLE {} IF NOT EXISTS (", self.name)?;       
-        write!(f, "{}", fmt_array(&self.cols, SEP))?;
-        if !self.checks.is_empty() {
-            write!(f, "{}", SEP)?;
+        let if_not_exists = if if_not_exists { "IF NOT EXISTS " } else { "" };
+        let mut sql = format!("CREATE TABLE {}{} (\n{}", if_not_exists, name, fmt_array(&self.cols, SEP));
+        for constraint in self.constraints() {
+            sql.push_str(SEP);
+            sql.push_str(&constraint);
+        }
+        sql.push_str("\n)");
+        let options = [(self.strict, "STRICT"), (self.without_rowid, "WITHOUT ROWID")]
+            .iter()
+            .filter(|it| it.0)
+            .map(|it| it.1)
+            .collect::<Vec<&str>>();
+        if !options.is_empty() {
+            sql.push(' ');
+            sql.push_str(&options.join(", "));
         }
-        write!(f, "{}", fmt_array(&self.checks, SEP))?;
-        if !self.foreign_keys.is_empty() {
-            write!(f, "{}", SEP)?;
+        sql.push(';');
+        sql
+    }
+
+    /// Table constraints followed by the table options, as SQL.
+    pub(crate) fn constraints(&self) -> Vec<String> {
+        self.primary_key.iter().map(|pk| pk.to_string())
+            .chain(self.uniques.iter().map(|u| u.to_string()))
+            .chain(self.checks.iter().map(|c| c.to_string()))
+            .chain(self.foreign_keys.iter().map(|fk| fk.to_string()))
+            .collect()
+    }
+
+    pub(crate) fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    pub(crate) fn is_without_rowid(&self) -> bool {
+        self.without_rowid
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.create_sql(&self.name, true))?;
+        for index in &self.indexes {
+            write!(f, "\n{}", index)?;
         }
-        write!(f, "{}", fmt_array(&self.foreign_keys, SEP))?;
-        write!(f, "\n);")?;
         Ok(())
     }
 }
 
+/// Table defined by a Rust struct, usually implemented with `#[derive(Table)]` of the `derive` feature.
+pub trait TableDefinition {
+    const NAME: &'static str;
+
+    /// `CREATE TABLE` statement of the table.
+    fn table() -> Table;
+}
+
+#[derive(Debug, Clone)]
+enum ColumnChange {
+    Type(Ident, String),
+    NotNull(Ident)
+}
+
+/// `ALTER TABLE` statements.
+///
+/// An `AlterTable` made by [`Table::alter`] knows the current definition of the table, so it can
+/// also change column types and constraints and drop any column. When SQLite cannot make the change
+/// directly, the table is rebuilt as described in <https://www.sqlite.org/lang_altertable.html#otheralter>:
+/// in a transaction and with foreign keys off, a new table is created, the rows copied, the old
+/// table dropped and the new one renamed, then its indexes are created and foreign keys checked
+/// with `PRAGMA foreign_key_check`. The statements manage their own transaction and leave foreign keys
+/// off, see [`Migration::without_transaction`](crate::migrate::Migration::without_transaction).
+///
+/// Columns are named as in the current definition, they are renamed last. Triggers on a rebuilt
+/// table are dropped with it and views using a dropped column have to be created again.
 #[derive(Debug, Default)]
 pub struct AlterTable {
-    name: String,
-    new_name: Option<String>,
+    name: Ident,
+    new_name: Option<Ident>,
     cols: Vec<Column>,
-    renames: Vec<(String, String)>,
-    drops: Vec<String>
+    renames: Vec<(Ident, Ident)>,
+    drops: Vec<Ident>,
+    changes: Vec<ColumnChange>,
+    checks: Vec<Check>,
+    foreign_keys: Vec<ForeignKey>,
+    definition: Option<Box<Table>>
 }
 
 impl AlterTable {
     fn new(name: &str) -> Self {
         Self {
-            name: name.to_owned(),
+            name: Ident::new(name),
             ..Self::default()
         }
     }
 
     pub fn rename_to(&mut self, new_name: &str) -> &mut Self {
-        self.new_name = Some(new_name.to_owned());
+        self.new_name = Some(Ident::new(new_name));
         self
     }
 
@@ -81,25 +319,115 @@ impl AlterTable {
     }
 
     pub fn rename_column(&mut self, col: &str, new_col: &str) -> &mut Self {
-        self.renames.push((col.to_owned(), new_col.to_owned()));
+        self.renames.push((Ident::new(col), Ident::new(new_col)));
         self
     }
 
+    /// Drops the column. With the definition of the table, the indexes and table constraints
+    /// involving the column are dropped too, rebuilding the table when needed.
     pub fn drop_column(&mut self, col: &str) -> &mut Self {
-        self.drops.push(col.to_owned());
+        self.drops.push(Ident::new(col));
+        self
+    }
+
+    /// Changes the declared type of a column, it needs the definition of the table.
+    pub fn alter_column_type(&mut self, col: &str, typ: &str) -> &mut Self {
+        self.changes.push(ColumnChange::Type(Ident::new(col), typ.to_owned()));
+        self
+    }
+
+    /// Makes a column `NOT NULL`, it needs the definition of the table.
+    pub fn set_not_null(&mut self, col: &str) -> &mut Self {
+        self.changes.push(ColumnChange::NotNull(Ident::new(col)));
         self
     }
+
+    /// Adds a table `CHECK` constraint, it needs the definition of the table.
+    pub fn add_check(&mut self, check: Check) -> &mut Self {
+        self.checks.push(check);
+        self
+    }
+
+    /// Adds a table foreign key, it needs the definition of the table.
+    pub fn add_foreign_key(&mut self, foreign_key: ForeignKey) -> &mut Self {
+        self.foreign_keys.push(foreign_key);
+        self
+    }
+
+    /// The table with the added columns, changes and drops applied.
+    fn altered(&self, table: &Table) -> Table {
+        let mut altered = table.clone();
+        for col in &self.cols {
+            altered.add_column(col.clone());
+        }
+        for change in &self.changes {
+            match change {
+                ColumnChange::Type(name, typ) => if let Some(col) = altered.column_mut(name.as_str()) {
+                    *col = col.clone().declared_type(typ);
+                },
+                ColumnChange::NotNull(name) => if let Some(col) = altered.column_mut(name.as_str()) {
+                    *col = col.clone().not_null();
+                }
+            }
+        }
+        for check in &self.checks {
+            altered.add_check(check.clone());
+        }
+        for fk in &self.foreign_keys {
+            altered.add_foreign_key(fk.clone());
+        }
+        for drop in &self.drops {
+            altered.remove_column(drop.as_str());
+        }
+        altered
+    }
+
+    /// Statements made from the definition of the table, the diff to the altered table then the renames.
+    fn statements(&self, table: &Table) -> Vec<String> {
+        let mut statements = diff_tables(table, &self.altered(table)).statements();
+        for r in &self.renames {
+            statements.push(format!("ALTER TABLE {} RENAME COLUMN {} TO {};", self.name, r.0, r.1));
+        }
+        if let Some(ref n) = self.new_name {
+            statements.push(format!("ALTER TABLE {} RENAME TO {};", self.name, n));
+        }
+        statements
+    }
+}
+
+impl Statement for AlterTable {
+    fn has_transaction(&self) -> bool {
+        self.definition.as_ref().is_some_and(|table| diff_tables(table, &self.altered(table)).requires_rebuild())
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        self.cols.iter().try_for_each(|col| col.validate())?;
+        let table = match self.definition {
+            Some(ref table) => table,
+            None if self.changes.is_empty() && self.checks.is_empty() && self.foreign_keys.is_empty() => return Ok(()),
+            None => return Err(Error::MissingDefinition { table: self.name.as_str().to_owned() })
+        };
+        let changed = self.changes.iter().map(|c| match c {
+            ColumnChange::Type(name, _) | ColumnChange::NotNull(name) => name
+        });
+        let columns = self.renames.iter().map(|r| &r.0).chain(self.drops.iter()).chain(changed);
+        for column in columns {
+            if table.column(column.as_str()).is_none() {
+                return Err(Error::UnknownColumn { table: self.name.as_str().to_owned(), column: column.as_str().to_owned() });
+            }
+        }
+        self.altered(table).validate()
+    }
 }
 
 impl fmt::Display for AlterTable {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref table) = self.definition {
+            return write!(f, "{}", self.statements(table).join("\n"));
+        }
         const SEP: &str = "\n";
-        let len = self.cols.len() + self.renames.len() + self.cols.len() + if self.new_name.is_some() { 1 } else { 0 };
+        let len = self.cols.len() + self.renames.len() + self.drops.len() + if self.new_name.is_some() { 1 } else { 0 };
         let mut strings = Vec::with_capacity(len);
-        if let Some(ref n) = self.new_name {
-            strings.push(format!("ALTER TABLE {} RENAME TO {};", self.name, n));
-        }
-        
         for r in &self.renames {
             strings.push(format!("ALTER TABLE {} RENAME COLUMN {} TO {};", self.name, r.0, r.1));
         }
@@ -112,12 +440,19 @@ impl fmt::Display for AlterTable {
             strings.push(format!("ALTER TABLE {} DROP COLUMN {};", self.name, d));
         }
 
+        // Last, the other statements use the current name.
+        if let Some(ref n) = self.new_name {
+            strings.push(format!("ALTER TABLE {} RENAME TO {};", self.name, n));
+        }
+
         write!(f, "{}", strings.join(SEP))?;
         Ok(())
     }
 }
 
-pub struct DropTable(String);
+pub struct DropTable(Ident);
+
+impl Statement for DropTable {}
 
 impl fmt::Display for DropTable {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -142,7 +477,7 @@ pub fn alter_table(name: &str) -> AlterTable {
 }
 
 pub fn drop_table(name: &str) -> DropTable {
-    DropTable(name.to_string())
+    DropTable(Ident::new(name))
 }
 
 #[cfg(test)]
@@ -152,12 +487,13 @@ mod tests {
 
     use super::*;
 
-    use crate::schema::{Column, ForeignKey, Check};    
+    use crate::schema::{Column, GeneratedColumnType, ForeignKey, Check, PrimaryKey, Unique, create_index};
+    use crate::ConflictResolution;    
 
     #[test]
     fn drop() {
         let td = drop_table("table").to_string();
-        assert_eq!(td, "DROP TABLE table;");
+        assert_eq!(td, "DROP TABLE \"table\";");
     }
 
     #[test]
@@ -172,6 +508,18 @@ mod tests {
         assert_eq!(tarc, "ALTER TABLE table_1 RENAME COLUMN col_1 TO col_2;");
     }
 
+    #[test]
+    fn alter_quoted() {
+        let atr = alter_table("order").rename_column("desc", "description").drop_column("group").to_string();
+        assert_eq!(atr, "ALTER TABLE \"order\" RENAME COLUMN \"desc\" TO description;\nALTER TABLE \"order\" DROP COLUMN \"group\";");
+    }
+
+    #[test]
+    fn alter_rename_last() {
+        let at = alter_table("t").rename_to("u").add_column(Column::new("c").nullable()).to_string();
+        assert_eq!(at, "ALTER TABLE t ADD COLUMN c INTEGER;\nALTER TABLE t RENAME TO u;");
+    }
+
     #[test]
     fn alter_drop_col() {
         let atdc = alter_table("table_1").drop_column("col_1").to_string();
@@ -184,6 +532,51 @@ mod tests {
         assert_eq!(atac, "ALTER TABLE table_1 ADD COLUMN col_1 INTEGER NOT NULL;");
     }
 
+    #[test]
+    fn alter_definition() {
+        let mut users = create_table("users");
+        users.add_column(Column::new("id").primary_key())
+            .add_column(Column::new("email").text().unique())
+            .add_column(Column::new("nick").text().nullable())
+            .add_column(Column::new("age").nullable())
+            .add_index(create_index("idx_users_nick").column("nick"));
+
+        let sql = users.alter().drop_column("nick").rename_column("age", "years").try_to_sql();
+        assert_eq!(sql, Ok("DROP INDEX IF EXISTS idx_users_nick;\n\
+            ALTER TABLE users DROP COLUMN nick;\n\
+            ALTER TABLE users RENAME COLUMN age TO years;".to_string()));
+
+        let sql = users.alter().set_not_null("nick").drop_column("email").add_check(Check::new("age > 0")).to_string();
+        assert_eq!(sql, "PRAGMA foreign_keys = OFF;\n\
+            BEGIN;\n\
+            CREATE TABLE new_users (\n\
+            id INTEGER PRIMARY KEY NOT NULL,\n\
+            nick TEXT NOT NULL,\n\
+            age INTEGER,\n \
+            CHECK(age > 0) \n\
+            );\n\
+            INSERT INTO new_users(id, nick, age) SELECT id, nick, age FROM users;\n\
+            DROP TABLE users;\n\
+            PRAGMA legacy_alter_table = ON;\n\
+            ALTER TABLE new_users RENAME TO users;\n\
+            PRAGMA legacy_alter_table = OFF;\n\
+            CREATE INDEX idx_users_nick ON users (nick);\n\
+            PRAGMA foreign_key_check;\n\
+            COMMIT;");
+
+        let sql = users.alter().alter_column_type("age", "REAL").add_foreign_key(ForeignKey::new("age").references("ages", "id")).to_string();
+        assert!(sql.contains("age REAL,\nFOREIGN KEY (age) REFERENCES ages (id)\n);"));
+
+        let err = alter_table("users").set_not_null("nick").try_to_sql().unwrap_err();
+        assert_eq!(err, Error::MissingDefinition { table: "users".into() });
+        let err = users.alter().alter_column_type("name", "TEXT").try_to_sql().unwrap_err();
+        assert_eq!(err, Error::UnknownColumn { table: "users".into(), column: "name".into() });
+        let mut pairs = create_table("pairs");
+        pairs.add_column(Column::new("a")).add_column(Column::new("b")).primary_key(&["a", "b"]).without_rowid();
+        let err = pairs.alter().drop_column("b").try_to_sql().unwrap_err();
+        assert_eq!(err, Error::MissingPrimaryKey { table: "pairs".into() });
+    }
+
     #[test]
     fn create_basic() {
         let col_1 = Column::new("id").primary_key();
@@ -192,7 +585,7 @@ mod tests {
         let col_1_str = col_1.to_string();
         let col_2_str = col_2.to_string();
 
-        let table_str = format!("CREATE TABLE table_1 IF NOT EXISTS (\n{},\n{}\n);", col_1_str, col_2_str);
+        let table_str = format!("CREATE TABLE IF NOT EXISTS table_1 (\n{},\n{}\n);", col_1_str, col_2_str);
 
         let ct = create_table("table_1")
             .add_column(col_1)
@@ -214,7 +607,7 @@ mod tests {
         let fk = ForeignKey::new("deptId").references("table_2", "id");
         let fk_str = fk.to_string();
 
-        let table_str = format!("CREATE TABLE table_1 IF NOT EXISTS (\n{},\n{},\n{},\n{}\n);", col_1_str, col_2_str, col_3_str, fk_str);
+        let table_str = format!("CREATE TABLE IF NOT EXISTS table_1 (\n{},\n{},\n{},\n{}\n);", col_1_str, col_2_str, col_3_str, fk_str);
         let ct = create_table("table_1")
             .add_column(col_1)
             .add_column(col_2)
@@ -238,7 +631,7 @@ mod tests {
         let constraint = Check::new("age > 0 AND age < 150");
         let con_str = constraint.to_string();
 
-        let table_str = format!("CREATE TABLE table_1 IF NOT EXISTS (\n{},\n{},\n{},\n{}\n);", col_1_str, col_2_str, col_3_str, con_str);
+        let table_str = format!("CREATE TABLE IF NOT EXISTS table_1 (\n{},\n{},\n{},\n{}\n);", col_1_str, col_2_str, col_3_str, con_str);
         let ct = create_table("table_1")
             .add_column(col_1)
             .add_column(col_2)
@@ -275,7 +668,7 @@ mod tests {
         let fk_2 = ForeignKey::new("roleId").references("table_3", "id");
         let fk_2_str = fk_2.to_string();
 
-        let table_str = format!("CREATE TABLE table_1 IF NOT EXISTS (\n{},\n{},\n{},\n{},\n{},\n{},\n{},\n{},\n{},\n{}\n);", col_1_str, col_2_str, col_3_str, col_4_str, col_5_str, col_6_str,
+        let table_str = format!("CREATE TABLE IF NOT EXISTS table_1 (\n{},\n{},\n{},\n{},\n{},\n{},\n{},\n{},\n{},\n{}\n);", col_1_str, col_2_str, col_3_str, col_4_str, col_5_str, col_6_str,
                 check_1_str, chesk_2_str, fk_1_str,fk_2_str);
         let ct = create_table("table_1")
             .add_column(col_1)
@@ -292,4 +685,159 @@ mod tests {
 
         assert_eq!(ct, table_str);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn validate() {
+        let err = create_table("empty").try_to_sql().unwrap_err();
+        assert_eq!(err, Error::NoColumns { table: "empty".into() });
+        assert_eq!(err.to_string(), "table empty has no columns");
+
+        let err = create_table("table_1")
+            .add_column(Column::new("id").primary_key())
+            .add_column(Column::new("deptId"))
+            .add_foreign_key(ForeignKey::new("deptId"))
+            .try_to_sql()
+            .unwrap_err();
+        assert_eq!(err, Error::MissingReference { column: "deptId".into() });
+        assert_eq!(err.to_string(), "foreign key on deptId does not reference any table");
+
+        let err = create_table("table_1")
+            .add_column(Column::new("id"))
+            .add_column(Column::new("id").text())
+            .build()
+            .unwrap_err();
+        assert_eq!(err, Error::DuplicateColumn { table: "table_1".into(), column: "id".into() });
+
+        let err = create_table("table_1")
+            .add_column(Column::new("id"))
+            .add_column(Column::new("ID").text())
+            .build()
+            .unwrap_err();
+        assert_eq!(err, Error::DuplicateColumn { table: "table_1".into(), column: "ID".into() });
+
+        let err = create_table("table_1")
+            .add_column(Column::new("id"))
+            .add_index(create_index("idx_empty"))
+            .build()
+            .unwrap_err();
+        assert_eq!(err, Error::NoIndexedColumns { index: "idx_empty".into() });
+
+        let sql = create_table("table_1").add_column(Column::new("id").primary_key()).try_to_sql();
+        assert_eq!(sql, Ok("CREATE TABLE IF NOT EXISTS table_1 (\nid INTEGER PRIMARY KEY NOT NULL\n);".to_string()));
+    }
+
+    #[test]
+    fn create_with_keys() {
+        let ct = create_table("user_groups")
+            .add_column(Column::new("userId"))
+            .add_column(Column::new("groupId"))
+            .add_column(Column::new("tenant_id"))
+            .add_column(Column::new("email").text())
+            .primary_key(&["userId", "groupId"])
+            .add_unique(&["tenant_id", "email"])
+            .add_unique_constraint(Unique::new(&["email"]).name("uq_email").on_conflict(ConflictResolution::Ignore))
+            .add_check(Check::new("userId > 0"))
+            .to_string();
+        assert_eq!(ct, "CREATE TABLE IF NOT EXISTS user_groups (\nuserId INTEGER NOT NULL,\ngroupId INTEGER NOT NULL,\ntenant_id INTEGER NOT NULL,\nemail TEXT NOT NULL,\nPRIMARY KEY (userId, groupId),\nUNIQUE (tenant_id, email),\nCONSTRAINT uq_email UNIQUE (email) ON CONFLICT IGNORE,\n CHECK(userId > 0) \n);");
+
+        let ct = create_table("t")
+            .add_column(Column::new("a"))
+            .set_primary_key(PrimaryKey::new(&["a"]).name("pk_t").on_conflict(ConflictResolution::Replace))
+            .to_string();
+        assert_eq!(ct, "CREATE TABLE IF NOT EXISTS t (\na INTEGER NOT NULL,\nCONSTRAINT pk_t PRIMARY KEY (a) ON CONFLICT REPLACE\n);");
+    }
+
+    #[test]
+    fn validate_keys() {
+        let err = create_table("t")
+            .add_column(Column::new("a").primary_key())
+            .add_column(Column::new("b"))
+            .primary_key(&["a", "b"])
+            .build()
+            .unwrap_err();
+        assert_eq!(err, Error::MultiplePrimaryKeys { table: "t".into() });
+
+        let err = create_table("t")
+            .add_column(Column::new("a"))
+            .add_unique(&["a", "c"])
+            .build()
+            .unwrap_err();
+        assert_eq!(err, Error::UnknownColumn { table: "t".into(), column: "c".into() });
+        assert_eq!(err.to_string(), "table t has no column c");
+
+        let table = create_table("t").add_column(Column::new("a")).add_unique(&["A"]).build();
+        assert!(table.is_ok());
+
+        let err = create_table("t").add_column(Column::new("a")).primary_key(&[]).build().unwrap_err();
+        assert_eq!(err, Error::NoConstraintColumns { table: "t".into() });
+
+        let err = create_table("t")
+            .add_column(Column::new("a"))
+            .add_column(Column::new("b").generated("a * 2", GeneratedColumnType::Stored))
+            .primary_key(&["a", "b"])
+            .build()
+            .unwrap_err();
+        assert_eq!(err, Error::GeneratedPrimaryKey { column: "b".into() });
+
+        let err = create_table("t")
+            .add_column(Column::new("a"))
+            .add_column(Column::new("b").generated("a * 2", GeneratedColumnType::Stored))
+            .primary_key(&["A", "B"])
+            .build()
+            .unwrap_err();
+        assert_eq!(err, Error::GeneratedPrimaryKey { column: "b".into() });
+    }
+
+    #[test]
+    fn create_with_options() {
+        let ct = create_table("t")
+            .add_column(Column::new("id").autoincrement())
+            .add_column(Column::new("v").any())
+            .strict()
+            .to_string();
+        assert_eq!(ct, "CREATE TABLE IF NOT EXISTS t (\nid INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,\nv ANY NOT NULL\n) STRICT;");
+
+        let ct = create_table("t")
+            .add_column(Column::new("a").text())
+            .add_column(Column::new("b").int_())
+            .primary_key(&["a", "b"])
+            .strict()
+            .without_rowid()
+            .try_to_sql();
+        assert_eq!(ct, Ok("CREATE TABLE IF NOT EXISTS t (\na TEXT NOT NULL,\nb INT NOT NULL,\nPRIMARY KEY (a, b)\n) STRICT, WITHOUT ROWID;".to_string()));
+    }
+
+    #[test]
+    fn validate_options() {
+        let err = create_table("t").add_column(Column::new("a")).without_rowid().build().unwrap_err();
+        assert_eq!(err, Error::MissingPrimaryKey { table: "t".into() });
+
+        let err = create_table("t").add_column(Column::new("id").autoincrement()).without_rowid().build().unwrap_err();
+        assert_eq!(err, Error::AutoincrementWithoutRowid { table: "t".into() });
+
+        let err = create_table("t").add_column(Column::new("id").primary_key()).add_column(Column::new("n").numeric()).strict().build().unwrap_err();
+        assert_eq!(err, Error::StrictType { table: "t".into(), column: "n".into(), typ: "NUMERIC".into() });
+
+        let ct = create_table("t").add_column(Column::new("id").declared_type("integer").autoincrement()).strict().build();
+        assert!(ct.is_ok());
+    }
+
+    #[test]
+    fn create_with_indexes() {
+        let col_1 = Column::new("id").primary_key();
+        let col_2 = Column::new("email").text();
+
+        let col_1_str = col_1.to_string();
+        let col_2_str = col_2.to_string();
+
+        let table_str = format!("CREATE TABLE IF NOT EXISTS table_1 (\n{},\n{}\n);\nCREATE UNIQUE INDEX idx_email ON table_1 (email);\nCREATE INDEX idx_id_email ON table_1 (id, email);", col_1_str, col_2_str);
+        let ct = create_table("table_1")
+            .add_column(col_1)
+            .add_column(col_2)
+            .add_index(create_index("idx_email").unique().column("email"))
+            .add_index(create_index("idx_id_email").on("other").columns(&["id", "email"]))
+            .to_string();
+
+        assert_eq!(ct, table_str);
+    }
+}
@@ -1,5 +1,8 @@
 use std::fmt::{self, Formatter};
 
+use crate::expr::Expr;
+use crate::{Ident, Statement, Error};
+
 #[derive(Debug, Clone, Copy)]
 enum Action {
     Before,
@@ -54,20 +57,20 @@ impl fmt::Display for TriggerType {
 
 #[derive(Debug, Clone)]
 struct Trigger {
-    name: String,
-    table: String,
+    name: Ident,
+    table: Ident,
     action: Action,
     event: Event,
     stmts: Vec<String>,
     typ: TriggerType,
-    when: Option<String>
+    when: Option<Expr>
 }
 
 impl Trigger {
     fn new(name: &str) -> Self {
         Self { 
-            name: name.to_owned(), 
-            table: String::new(), 
+            name: Ident::new(name), 
+            table: Ident::default(), 
             action: Action::Before, 
             event: Event::Insert, 
             stmts: Vec::new(), 
@@ -77,7 +80,7 @@ impl Trigger {
     }
 
     fn on(&mut self, table: &str) {
-        self.table = table.to_owned();
+        self.table = Ident::new(table);
     }
 
     fn before(&mut self) {
@@ -117,8 +120,8 @@ impl Trigger {
         self.stmts.extend_from_slice(&arr);
     }
 
-    fn when(&mut self, expr: &str) {
-        self.when = Some(expr.to_owned());
+    fn when(&mut self, expr: Expr) {
+        self.when = Some(expr);
     }
 }
 
@@ -197,8 +200,8 @@ impl TriggerWithEvent {
 pub struct TriggerWithTable(Trigger);
 
 impl TriggerWithTable {
-    pub fn when(mut self, expr: &str) -> Self {
-        self.0.when(expr);
+    pub fn when(mut self, expr: impl Into<Expr>) -> Self {
+        self.0.when(expr.into());
         self
     }
 
@@ -227,20 +230,45 @@ impl TriggerFull {
     }
 }
 
+impl TriggerFull {
+    pub fn name(&self) -> &str {
+        self.0.name.as_str()
+    }
+
+    pub(crate) fn table(&self) -> &Ident {
+        &self.0.table
+    }
+
+    pub(crate) fn is_instead_of(&self) -> bool {
+        matches!(self.0.action, Action::InsteadOf)
+    }
+}
+
 impl fmt::Display for TriggerFull {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.0.fmt(f)
     }
 }
 
-pub struct TriggerDrop(String);
+impl Statement for TriggerFull {
+    fn validate(&self) -> Result<(), Error> {
+        if self.0.stmts.is_empty() {
+            return Err(Error::NoTriggerStatements { trigger: self.0.name.as_str().to_owned() });
+        }
+        Ok(())
+    }
+}
+
+pub struct TriggerDrop(Ident);
 
 impl TriggerDrop {
     fn new(name: &str) -> Self {
-        Self(name.to_owned())
+        Self(Ident::new(name))
     }
 }
 
+impl Statement for TriggerDrop {}
+
 impl fmt::Display for TriggerDrop {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "DROP TRIGGER IF EXISTS {};", self.0)
@@ -258,6 +286,8 @@ pub fn drop_trigger(name: &str) -> TriggerDrop {
 #[cfg(test)]
 mod tests {
     use super::{create_trigger, drop_trigger};
+    use crate::expr::col;
+    use crate::{Statement, Error};
 
     #[test]
     fn trigger_drop() {
@@ -278,44 +308,56 @@ mod tests {
         let trg8 = create_trigger("MyTrigger").before().insert().on("table").statement("stmt");
         let trg9 = create_trigger("MyTrigger").before().update().on("table").statement("stmt");
 
-        assert_eq!(trg1.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON table\nBEGIN\nstmt;\nEND;");
-        assert_eq!(trg2.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER INSERT ON table\nBEGIN\nstmt;\nEND;");
-        assert_eq!(trg3.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER UPDATE ON table\nBEGIN\nstmt;\nEND;");
-        assert_eq!(trg4.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger INSTEAD OF DELETE ON table\nBEGIN\nstmt;\nEND;");
-        assert_eq!(trg5.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger INSTEAD OF INSERT ON table\nBEGIN\nstmt;\nEND;");
-        assert_eq!(trg6.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger INSTEAD OF UPDATE ON table\nBEGIN\nstmt;\nEND;");
-        assert_eq!(trg7.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger BEFORE DELETE ON table\nBEGIN\nstmt;\nEND;");
-        assert_eq!(trg8.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger BEFORE INSERT ON table\nBEGIN\nstmt;\nEND;");
-        assert_eq!(trg9.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger BEFORE UPDATE ON table\nBEGIN\nstmt;\nEND;");
+        assert_eq!(trg1.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON \"table\"\nBEGIN\nstmt;\nEND;");
+        assert_eq!(trg2.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER INSERT ON \"table\"\nBEGIN\nstmt;\nEND;");
+        assert_eq!(trg3.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER UPDATE ON \"table\"\nBEGIN\nstmt;\nEND;");
+        assert_eq!(trg4.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger INSTEAD OF DELETE ON \"table\"\nBEGIN\nstmt;\nEND;");
+        assert_eq!(trg5.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger INSTEAD OF INSERT ON \"table\"\nBEGIN\nstmt;\nEND;");
+        assert_eq!(trg6.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger INSTEAD OF UPDATE ON \"table\"\nBEGIN\nstmt;\nEND;");
+        assert_eq!(trg7.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger BEFORE DELETE ON \"table\"\nBEGIN\nstmt;\nEND;");
+        assert_eq!(trg8.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger BEFORE INSERT ON \"table\"\nBEGIN\nstmt;\nEND;");
+        assert_eq!(trg9.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger BEFORE UPDATE ON \"table\"\nBEGIN\nstmt;\nEND;");
     }
 
     #[test]
     fn trigger_create_temp() {
         let trg1 = create_trigger("MyTrigger").temporary().after().delete().on("table").statement("stmt"); 
-        assert_eq!(trg1.to_string(), "CREATE TEMP TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON table\nBEGIN\nstmt;\nEND;");
+        assert_eq!(trg1.to_string(), "CREATE TEMP TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON \"table\"\nBEGIN\nstmt;\nEND;");
     }
 
     #[test]
     fn trigger_create_when() {
         let trg1 = create_trigger("MyTrigger").temporary().after().delete().on("table").when("x < y").statement("stmt"); 
-        assert_eq!(trg1.to_string(), "CREATE TEMP TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON table\nWHEN x < y\nBEGIN\nstmt;\nEND;");
+        assert_eq!(trg1.to_string(), "CREATE TEMP TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON \"table\"\nWHEN x < y\nBEGIN\nstmt;\nEND;");
+    }
+
+    #[test]
+    fn trigger_create_when_expr() {
+        let trg1 = create_trigger("MyTrigger").after().update().on("table").when(col("new.x").ne(col("old.x")).or(col("new.x").is_null())).statement("stmt");
+        assert_eq!(trg1.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER UPDATE ON \"table\"\nWHEN new.x <> old.x OR new.x IS NULL\nBEGIN\nstmt;\nEND;");
     }
 
     #[test]
     fn trigger_create_multi_stmt() {
         let trg1 = create_trigger("MyTrigger").after().delete().on("table").when("x < y").statement("stmt0").statement("stmt1");
-        assert_eq!(trg1.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON table\nWHEN x < y\nBEGIN\nstmt0;\nstmt1;\nEND;");
+        assert_eq!(trg1.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON \"table\"\nWHEN x < y\nBEGIN\nstmt0;\nstmt1;\nEND;");
 
         let trg2 = create_trigger("MyTrigger").after().delete().on("table").when("x < y").statements(&["stmt0", "stmt1"]);
-        assert_eq!(trg2.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON table\nWHEN x < y\nBEGIN\nstmt0;\nstmt1;\nEND;");
+        assert_eq!(trg2.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON \"table\"\nWHEN x < y\nBEGIN\nstmt0;\nstmt1;\nEND;");
 
         let trg3 = create_trigger("MyTrigger").after().delete().on("table").when("x < y").statement("stmt0").statements(&["stmt1", "stmt2"]).statement("stmt3");
-        assert_eq!(trg3.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON table\nWHEN x < y\nBEGIN\nstmt0;\nstmt1;\nstmt2;\nstmt3;\nEND;");
+        assert_eq!(trg3.to_string(), "CREATE TRIGGER IF NOT EXISTS MyTrigger AFTER DELETE ON \"table\"\nWHEN x < y\nBEGIN\nstmt0;\nstmt1;\nstmt2;\nstmt3;\nEND;");
+    }
+
+    #[test]
+    fn trigger_without_statements() {
+        let trg = create_trigger("MyTrigger").after().delete().on("table").statements(&[]);
+        assert_eq!(trg.try_to_sql(), Err(Error::NoTriggerStatements { trigger: "MyTrigger".into() }));
     }
 
     #[test]
     fn trigger_create_full() {
         let trg1 = create_trigger("MyTrigger").temporary().before().update().on("table").when("x < y").statement("stmt0").statement("stmt1");
-        assert_eq!(trg1.to_string(), "CREATE TEMP TRIGGER IF NOT EXISTS MyTrigger BEFORE UPDATE ON table\nWHEN x < y\nBEGIN\nstmt0;\nstmt1;\nEND;");
+        assert_eq!(trg1.to_string(), "CREATE TEMP TRIGGER IF NOT EXISTS MyTrigger BEFORE UPDATE ON \"table\"\nWHEN x < y\nBEGIN\nstmt0;\nstmt1;\nEND;");
     }
 }
\ No newline at end of file
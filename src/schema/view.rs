@@ -1,6 +1,9 @@
 
 use std::fmt;
 
+use crate::{Ident, Statement};
+use crate::ident::fmt_idents;
+
 #[derive(Debug, Clone, Copy)]
 pub enum ViewType {
     Temporary,
@@ -19,16 +22,16 @@ impl fmt::Display for ViewType {
 
 #[derive(Debug, Clone)]
 pub struct ViewCreate {
-    name: String,
+    name: Ident,
     select: String,
     typ: ViewType,
-    columns: Vec<String>
+    columns: Vec<Ident>
 }
 
 impl ViewCreate {
     pub fn new(name: &str) -> Self {
         Self {
-            name: name.to_string(),
+            name: Ident::new(name),
             select: String::new(),
             typ: ViewType::Normal,
             columns: Vec::new()
@@ -36,11 +39,11 @@ impl ViewCreate {
     }
 
     pub fn column(&mut self, col: &str) {
-        self.columns.push(col.to_owned());
+        self.columns.push(Ident::new(col));
     }
 
     pub fn columns(&mut self, cols: &[&str]) {
-        let arr: Vec<String> = cols.iter().map(|s| s.to_string()).collect();
+        let arr: Vec<Ident> = cols.iter().map(|s| Ident::new(s)).collect();
         self.columns.extend_from_slice(&arr);        
     }
 
@@ -55,7 +58,7 @@ impl ViewCreate {
 
 impl fmt::Display for ViewCreate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let columns = if !self.columns.is_empty() { format!("({})", self.columns.join(", ")) } else { "".to_string() };
+        let columns = if !self.columns.is_empty() { format!("({})", fmt_idents(&self.columns)) } else { "".to_string() };
         write!(f, "CREATE {}VIEW IF NOT EXISTS {}{} AS {};", self.typ, self.name, columns, self.select)?;
         Ok(())
     }
@@ -99,12 +102,16 @@ impl ViewCreateStmt {
     }
 
     pub fn name(&self) -> &str {
-        &(self.0.name)
+        self.0.name.as_str()
     }
 
-    pub fn columns(&self) -> &[String] {
+    pub fn columns(&self) -> &[Ident] {
         &(self.0.columns)
     }
+
+    pub(crate) fn select(&self) -> &str {
+        &self.0.select
+    }
 }
 
 impl fmt::Display for ViewCreateStmt {
@@ -113,8 +120,12 @@ impl fmt::Display for ViewCreateStmt {
     }
 }
 
+impl Statement for ViewCreateStmt {}
+
 #[derive(Debug)]
-pub struct ViewDrop(String);
+pub struct ViewDrop(Ident);
+
+impl Statement for ViewDrop {}
 
 impl fmt::Display for ViewDrop {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -123,17 +134,154 @@ impl fmt::Display for ViewDrop {
     }
 }
 
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Punct(char)
+}
+
+impl Token {
+    /// Name of a table, column or alias, keywords are not names unless quoted.
+    fn name(&self) -> Option<&str> {
+        match self {
+            Self::Word(w) if !crate::is_keyword(w) => Some(w),
+            Self::Quoted(q) => Some(q),
+            _ => None
+        }
+    }
+
+    /// Name right after `FROM`, `JOIN` or `.`, where SQLite accepts most keywords unquoted too.
+    fn table_name(&self) -> Option<&str> {
+        match self {
+            Self::Word(w) | Self::Quoted(w) => Some(w),
+            Self::Punct(_) => None
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Self::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+}
+
+/// Splits SQL into words, quoted identifiers and punctuation, string literals and comments are dropped.
+fn tokenize(sql: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                while let Some(c) = chars.next() {
+                    if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                }
+            },
+            '"' | '`' | '[' => {
+                let end = if c == '[' { ']' } else { c };
+                let mut quoted = String::new();
+                while let Some(c) = chars.next() {
+                    if c == end && (end == ']' || chars.next_if_eq(&end).is_none()) {
+                        break;
+                    }
+                    quoted.push(c);
+                }
+                tokens.push(Token::Quoted(quoted));
+            },
+            '-' if chars.next_if_eq(&'-').is_some() => {
+                chars.by_ref().find(|&c| c == '\n');
+            },
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let mut last = ' ';
+                chars.by_ref().find(|&c| std::mem::replace(&mut last, c) == '*' && c == '/');
+            },
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '$') {
+                    word.push(c);
+                }
+                tokens.push(Token::Word(word));
+            },
+            c if c.is_whitespace() => {},
+            c => tokens.push(Token::Punct(c))
+        }
+    }
+    tokens
+}
+
+/// Tables and views a select reads from, ie. named after `FROM`, `JOIN` and commas of the `FROM` clause.
+/// Common table expressions and table-valued functions are left out.
+pub(crate) fn referenced_tables(select: &str) -> Vec<String> {
+    let tokens = tokenize(select);
+    let mut tables: Vec<String> = Vec::new();
+    let mut ctes = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i].is_keyword("FROM") || tokens[i].is_keyword("JOIN") {
+            i += 1;
+            while let Some(mut name) = tokens.get(i).and_then(Token::table_name) {
+                i += 1;
+                if tokens.get(i) == Some(&Token::Punct('.')) {
+                    if let Some(n) = tokens.get(i + 1).and_then(Token::table_name) {
+                        name = n;
+                        i += 2;
+                    }
+                }
+                if tokens.get(i) == Some(&Token::Punct('(')) {
+                    break;
+                }
+                if !tables.iter().any(|t| t.eq_ignore_ascii_case(name)) {
+                    tables.push(name.to_owned());
+                }
+                if tokens.get(i).is_some_and(|t| t.is_keyword("AS")) {
+                    i += 1;
+                }
+                if tokens.get(i).and_then(Token::name).is_some() {
+                    i += 1;
+                }
+                if tokens.get(i) != Some(&Token::Punct(',')) {
+                    break;
+                }
+                i += 1;
+            }
+            continue;
+        }
+        if let Some(name) = tokens[i].name() {
+            // `name AS (`, `name(a, b) AS (` and `name AS [NOT] MATERIALIZED (` define common table expressions.
+            let mut j = i + 1;
+            if tokens.get(j) == Some(&Token::Punct('(')) {
+                while tokens.get(j).is_some_and(|t| *t != Token::Punct(')')) {
+                    j += 1;
+                }
+                j += 1;
+            }
+            if tokens.get(j).is_some_and(|t| t.is_keyword("AS")) {
+                j += 1;
+                while tokens.get(j).is_some_and(|t| t.is_keyword("NOT") || t.is_keyword("MATERIALIZED")) {
+                    j += 1;
+                }
+                if tokens.get(j) == Some(&Token::Punct('(')) {
+                    ctes.push(name.to_owned());
+                }
+            }
+        }
+        i += 1;
+    }
+    tables.retain(|t| !ctes.iter().any(|c: &String| c.eq_ignore_ascii_case(t)));
+    tables
+}
+
 pub fn create_view(name: &str) -> ViewCreateDef {
     ViewCreateDef::new(name)
 }
 
 pub fn drop_view(name: &str) -> ViewDrop {
-    ViewDrop(name.to_string())
+    ViewDrop(Ident::new(name))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{create_view, drop_view};
+    use super::{create_view, drop_view, referenced_tables};
 
     #[test]
     fn create() {
@@ -164,9 +312,25 @@ mod tests {
         assert_eq!(view.to_string(), format!("CREATE TEMP VIEW IF NOT EXISTS my_view(a, b, c, d) AS {};", SELECT));
     }
 
+    #[test]
+    fn quoted_names() {
+        const SELECT: &str = "SELECT Username FROM users";
+
+        let view = create_view("user view").columns(&["user name"]).as_(SELECT);
+        assert_eq!(view.to_string(), format!("CREATE VIEW IF NOT EXISTS \"user view\"(\"user name\") AS {};", SELECT));
+    }
+
     #[test]
     fn drop_() {
         let dview = drop_view("my_view");
         assert_eq!(dview.to_string(), "DROP VIEW IF EXISTS my_view;");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn tables() {
+        assert_eq!(referenced_tables("SELECT * FROM users u JOIN \"order\" AS o ON o.user = u.id"), ["users", "order"]);
+        assert_eq!(referenced_tables("SELECT a FROM main.t1, t2 x, json_each(t2.data) WHERE a = 'FROM nope'"), ["t1", "t2"]);
+        assert_eq!(referenced_tables("WITH recent(id) AS (SELECT id FROM posts) SELECT * FROM recent -- FROM comment"), ["posts"]);
+        assert_eq!(referenced_tables("SELECT 1 FROM (SELECT id FROM users)"), ["users"]);
+    }
+}
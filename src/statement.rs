@@ -0,0 +1,67 @@
+use std::fmt;
+
+use crate::{Error, Value};
+
+/// Common interface of the statements produced by the builders.
+///
+/// `Display` renders the statement as it is, [`try_to_sql`](Statement::try_to_sql) and
+/// [`build`](Statement::build) validate its structure first, and check that its values can be bound
+/// to its placeholders.
+pub trait Statement: fmt::Display {
+    /// Values bound to the statement, in the order their placeholders appear in the SQL text.
+    fn params(&self) -> Vec<Value> {
+        Vec::new()
+    }
+
+    fn to_sql_with_params(&self) -> (String, Vec<Value>) {
+        (self.to_string(), self.params())
+    }
+
+    /// Checks the structure of the statement, eg. that every inserted row has as many values as there are columns.
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Whether the statement begins and commits its own transaction, eg. a table rebuild, so it cannot
+    /// run inside another one.
+    fn has_transaction(&self) -> bool {
+        false
+    }
+
+    fn try_to_sql(&self) -> Result<String, Error> {
+        self.build().map(|(sql, _)| sql)
+    }
+
+    /// Validated [`to_sql_with_params`](Statement::to_sql_with_params).
+    fn build(&self) -> Result<(String, Vec<Value>), Error> {
+        self.validate()?;
+        let (sql, params) = self.to_sql_with_params();
+        check_placeholders(&sql, params.len())?;
+        Ok((sql, params))
+    }
+}
+
+/// Checks that anonymous placeholders are not mixed with numbered or named ones and that, when the
+/// statement has bound values, every anonymous placeholder is one of them.
+fn check_placeholders(sql: &str, params: usize) -> Result<(), Error> {
+    let bytes = sql.as_bytes();
+    let (mut anonymous, mut other) = (0, false);
+    let mut i = 0;
+    while i < bytes.len() {
+        let ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b >= 0x80;
+        match bytes[i] {
+            q @ (b'\'' | b'"' | b'`') => i += sql[i + 1..].find(q as char).map_or(sql.len(), |p| p + 1),
+            b'[' => i += sql[i..].find(']').unwrap_or(sql.len()),
+            b'-' if bytes.get(i + 1) == Some(&b'-') => i += sql[i..].find('\n').unwrap_or(sql.len()),
+            b'?' if bytes.get(i + 1).is_some_and(u8::is_ascii_digit) => other = true,
+            b'?' => anonymous += 1,
+            b':' | b'@' | b'$' if (i == 0 || !ident(bytes[i - 1])) && bytes.get(i + 1).is_some_and(|&b| ident(b)) => other = true,
+            _ => {}
+        }
+        i += 1;
+    }
+    if (anonymous > 0 && other) || (params > 0 && anonymous != params) {
+        return Err(Error::MixedPlaceholders);
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+
+use crate::ident::{text_literal, blob_literal};
+
+/// SQLite value, either inlined into the statement as a literal or bound to a placeholder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>)
+}
+
+/// Renders the value as an SQL literal.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "NULL"),
+            Self::Integer(v) => write!(f, "{}", v),
+            // SQLite stores NaN as NULL and has no literal for infinity, but overflows 9e999 to it.
+            Self::Real(v) if v.is_nan() => write!(f, "NULL"),
+            Self::Real(v) if v.is_infinite() => write!(f, "{}9e999", if *v < 0.0 { "-" } else { "" }),
+            Self::Real(v) => write!(f, "{:?}", v),
+            Self::Text(v) => write!(f, "{}", text_literal(v)),
+            Self::Blob(v) => write!(f, "{}", blob_literal(v))
+        }
+    }
+}
+
+macro_rules! implement_value_from {
+    ($variant: ident, $t: ty) => {
+        impl From<$t> for Value {
+            fn from(value: $t) -> Self {
+                Self::$variant(value.into())
+            }
+        }
+    }
+}
+
+implement_value_from!(Integer, i64);
+implement_value_from!(Integer, i32);
+implement_value_from!(Integer, u32);
+implement_value_from!(Integer, i16);
+implement_value_from!(Integer, u16);
+implement_value_from!(Integer, i8);
+implement_value_from!(Integer, u8);
+implement_value_from!(Real, f64);
+implement_value_from!(Real, f32);
+implement_value_from!(Text, String);
+implement_value_from!(Text, &str);
+implement_value_from!(Blob, Vec<u8>);
+implement_value_from!(Blob, &[u8]);
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Integer(value as i64)
+    }
+}
+
+impl<T> From<Option<T>> for Value where T: Into<Value> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(v) => v.into(),
+            None => Self::Null
+        }
+    }
+}
+
+/// Parameter placeholder whose value is supplied when the statement is executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placeholder {
+    /// `?`
+    Anonymous,
+    /// `?NNN`
+    Numbered(u32),
+    /// `:name`
+    Named(String)
+}
+
+impl fmt::Display for Placeholder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Anonymous => write!(f, "?"),
+            Self::Numbered(n) => write!(f, "?{}", n),
+            Self::Named(name) => write!(f, ":{}", name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Value, Placeholder};
+
+    #[test]
+    fn literals() {
+        assert_eq!(Value::from(1).to_string(), "1");
+        assert_eq!(Value::from(1.5).to_string(), "1.5");
+        assert_eq!(Value::from(2.0).to_string(), "2.0");
+        assert_eq!(Value::from(true).to_string(), "1");
+        assert_eq!(Value::from("O'Hara").to_string(), "'O''Hara'");
+        assert_eq!(Value::from(vec![0x01u8, 0xAB]).to_string(), "X'01AB'");
+        assert_eq!(Value::from(None::<i64>).to_string(), "NULL");
+        assert_eq!(Value::from(f64::NAN).to_string(), "NULL");
+        assert_eq!(Value::from(f64::INFINITY).to_string(), "9e999");
+        assert_eq!(Value::from(f64::NEG_INFINITY).to_string(), "-9e999");
+    }
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(Placeholder::Anonymous.to_string(), "?");
+        assert_eq!(Placeholder::Numbered(3).to_string(), "?3");
+        assert_eq!(Placeholder::Named("login".into()).to_string(), ":login");
+    }
+}
@@ -0,0 +1,281 @@
+//! `#[derive(Table)]` for yaqub, use it through the `derive` feature of yaqub.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, Lit, LitStr, Type};
+
+/// Implements `yaqub::schema::TableDefinition` for a struct with named fields and adds a module,
+/// named after the table, with a `yaqub::query::TypedColumn` constant for every column.
+///
+/// Struct attributes, `#[table(...)]`:
+/// - `name = "users"`, the table name, by default the struct name in snake case
+/// - `module = "users"`, the module with the column constants, by default the table name
+/// - `strict`, `without_rowid`
+///
+/// Field attributes, `#[column(...)]`:
+/// - `name = "eMail"`, the column name, by default the field name
+/// - `declared_type = "VARCHAR(255)"`, overrides the type mapped from the field type
+/// - `primary_key`, `autoincrement`, `unique`
+/// - `check = "length(email) > 3"`, raw SQL
+/// - `default = "CURRENT_TIMESTAMP"`, raw SQL, or a number or bool literal, `default = 0`
+/// - `collate = "NOCASE"`
+/// - `references = "users(id)"` or `references = "users"`, with `on_delete = "cascade"` and `on_update = "set null"`
+/// - `generated = "price * quantity"`, virtual unless `stored` is given
+#[proc_macro_derive(Table, attributes(table, column))]
+pub fn derive_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+#[derive(Default)]
+struct TableAttrs {
+    name: Option<String>,
+    module: Option<LitStr>,
+    strict: bool,
+    without_rowid: bool
+}
+
+impl TableAttrs {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("table")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    attrs.name = Some(string(&meta)?.value());
+                } else if meta.path.is_ident("module") {
+                    attrs.module = Some(string(&meta)?);
+                } else if meta.path.is_ident("strict") {
+                    attrs.strict = true;
+                } else if meta.path.is_ident("without_rowid") {
+                    attrs.without_rowid = true;
+                } else {
+                    return Err(meta.error("unknown table attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attrs)
+    }
+}
+
+#[derive(Default)]
+struct ColumnAttrs {
+    name: Option<String>,
+    declared_type: Option<String>,
+    primary_key: bool,
+    autoincrement: bool,
+    unique: bool,
+    check: Option<String>,
+    default: Option<Lit>,
+    collate: Option<String>,
+    references: Option<(String, Vec<String>)>,
+    on_delete: Option<Ident>,
+    on_update: Option<Ident>,
+    generated: Option<String>,
+    stored: bool
+}
+
+impl ColumnAttrs {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("column")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    attrs.name = Some(string(&meta)?.value());
+                } else if meta.path.is_ident("declared_type") {
+                    attrs.declared_type = Some(string(&meta)?.value());
+                } else if meta.path.is_ident("primary_key") {
+                    attrs.primary_key = true;
+                } else if meta.path.is_ident("autoincrement") {
+                    attrs.autoincrement = true;
+                } else if meta.path.is_ident("unique") {
+                    attrs.unique = true;
+                } else if meta.path.is_ident("check") {
+                    attrs.check = Some(string(&meta)?.value());
+                } else if meta.path.is_ident("default") {
+                    let lit: Lit = meta.value()?.parse()?;
+                    match lit {
+                        Lit::Str(_) | Lit::Int(_) | Lit::Float(_) | Lit::Bool(_) => attrs.default = Some(lit),
+                        _ => return Err(syn::Error::new(lit.span(), "expected a string, number or bool"))
+                    }
+                } else if meta.path.is_ident("collate") {
+                    attrs.collate = Some(string(&meta)?.value());
+                } else if meta.path.is_ident("references") {
+                    attrs.references = Some(parse_references(&string(&meta)?)?);
+                } else if meta.path.is_ident("on_delete") {
+                    attrs.on_delete = Some(parse_action(&string(&meta)?)?);
+                } else if meta.path.is_ident("on_update") {
+                    attrs.on_update = Some(parse_action(&string(&meta)?)?);
+                } else if meta.path.is_ident("generated") {
+                    attrs.generated = Some(string(&meta)?.value());
+                } else if meta.path.is_ident("stored") {
+                    attrs.stored = true;
+                } else {
+                    return Err(meta.error("unknown column attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attrs)
+    }
+
+    /// Builder calls applied to `Column::of`, in the order of the column constraints.
+    fn modifiers(&self) -> TokenStream2 {
+        let mut calls = TokenStream2::new();
+        if let Some(ref typ) = self.declared_type {
+            calls.extend(quote!(.declared_type(#typ)));
+        }
+        if self.autoincrement {
+            calls.extend(quote!(.autoincrement()));
+        } else if self.primary_key {
+            calls.extend(quote!(.primary_key()));
+        }
+        if self.unique {
+            calls.extend(quote!(.unique()));
+        }
+        if let Some(ref check) = self.check {
+            calls.extend(quote!(.check(::yaqub::expr::raw(#check))));
+        }
+        match self.default {
+            Some(Lit::Str(ref sql)) => calls.extend(quote!(.default_value(#sql))),
+            Some(ref lit) => calls.extend(quote!(.default_literal(#lit))),
+            None => {}
+        }
+        if let Some(ref collation) = self.collate {
+            calls.extend(quote!(.collate(#collation)));
+        }
+        if let Some((ref table, ref columns)) = self.references {
+            let mut refs = quote!(::yaqub::schema::References::new(#table, &[#(#columns),*]));
+            if let Some(ref action) = self.on_delete {
+                refs.extend(quote!(.on_delete(::yaqub::schema::ForeignKeyAction::#action)));
+            }
+            if let Some(ref action) = self.on_update {
+                refs.extend(quote!(.on_update(::yaqub::schema::ForeignKeyAction::#action)));
+            }
+            calls.extend(quote!(.references(#refs)));
+        }
+        if let Some(ref expr) = self.generated {
+            let typ = if self.stored { quote!(Stored) } else { quote!(Virtual) };
+            calls.extend(quote!(.generated(#expr, ::yaqub::schema::GeneratedColumnType::#typ)));
+        }
+        calls
+    }
+}
+
+fn string(meta: &ParseNestedMeta) -> syn::Result<LitStr> {
+    meta.value()?.parse()
+}
+
+/// `table` or `table(a, b)`.
+fn parse_references(lit: &LitStr) -> syn::Result<(String, Vec<String>)> {
+    let value = lit.value();
+    let (table, columns) = match value.split_once('(') {
+        Some((table, rest)) => match rest.strip_suffix(')') {
+            Some(columns) => (table, columns.split(',').map(|c| c.trim().to_owned()).collect()),
+            None => return Err(syn::Error::new(lit.span(), "expected `table(column, ...)`"))
+        },
+        None => (value.as_str(), Vec::new())
+    };
+    if table.trim().is_empty() {
+        return Err(syn::Error::new(lit.span(), "missing referenced table"));
+    }
+    Ok((table.trim().to_owned(), columns))
+}
+
+fn parse_action(lit: &LitStr) -> syn::Result<Ident> {
+    let variant = match lit.value().to_ascii_uppercase().as_str() {
+        "SET NULL" => "SetNull",
+        "SET DEFAULT" => "SetDefault",
+        "RESTRICT" => "Restrict",
+        "NO ACTION" => "NoAction",
+        "CASCADE" => "Cascade",
+        _ => return Err(syn::Error::new(lit.span(), "expected one of `set null`, `set default`, `restrict`, `no action` or `cascade`"))
+    };
+    Ok(Ident::new(variant, lit.span()))
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&input.generics, "Table cannot be derived for generic structs"));
+    }
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(&input.ident, "Table can only be derived for structs with named fields"))
+        },
+        _ => return Err(syn::Error::new_spanned(&input.ident, "Table can only be derived for structs"))
+    };
+    let attrs = TableAttrs::parse(&input)?;
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let table = attrs.name.unwrap_or_else(|| snake_case(&ident.to_string()));
+    let module: Ident = match attrs.module {
+        Some(ref lit) => lit.parse()?,
+        None => syn::parse_str(&table).map_err(|_| syn::Error::new(Span::call_site(), format!("table name `{}` is not a valid module name, set it with #[table(module = \"...\")]", table)))?
+    };
+
+    let mut columns = Vec::new();
+    let mut constants = Vec::new();
+    for field in fields {
+        let attrs = ColumnAttrs::parse(field)?;
+        let field_ident = field.ident.as_ref().expect("named field");
+        let name = attrs.name.clone().unwrap_or_else(|| field_ident.unraw().to_string());
+        let typ: &Type = &field.ty;
+        let modifiers = attrs.modifiers();
+        columns.push(quote! {
+            table.add_column(::yaqub::schema::Column::of::<#typ>(#name) #modifiers);
+        });
+        constants.push(quote! {
+            pub const #field_ident: ::yaqub::query::TypedColumn<#typ> = ::yaqub::query::TypedColumn::new(#table, #name);
+        });
+    }
+    let mut options = TokenStream2::new();
+    if attrs.strict {
+        options.extend(quote!(table.strict();));
+    }
+    if attrs.without_rowid {
+        options.extend(quote!(table.without_rowid();));
+    }
+    let module_doc = format!("Columns of the `{}` table.", table);
+
+    Ok(quote! {
+        impl ::yaqub::schema::TableDefinition for #ident {
+            const NAME: &'static str = #table;
+
+            fn table() -> ::yaqub::schema::Table {
+                let mut table = ::yaqub::schema::create_table(#table);
+                #(#columns)*
+                #options
+                table
+            }
+        }
+
+        #[doc = #module_doc]
+        #[allow(non_upper_case_globals)]
+        #vis mod #module {
+            #[allow(unused_imports)]
+            use super::*;
+
+            pub const TABLE: &str = #table;
+            #(#constants)*
+        }
+    })
+}
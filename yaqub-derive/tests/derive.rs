@@ -0,0 +1,112 @@
+use yaqub::expr::{col, lit, Expr};
+use yaqub::query::{select, update};
+use yaqub::schema::{SqlType, Table, TableDefinition};
+use yaqub::Statement;
+
+#[derive(Table)]
+#[table(name = "users", strict)]
+#[allow(dead_code)]
+struct User {
+    #[column(primary_key, autoincrement)]
+    id: i64,
+    #[column(unique, collate = "NOCASE", check = "length(email) > 3")]
+    email: String,
+    #[column(name = "displayName")]
+    display_name: Option<String>,
+    #[column(default = 1)]
+    active: bool,
+    #[column(default = "CURRENT_TIMESTAMP")]
+    created: String
+}
+
+#[derive(Table)]
+#[allow(dead_code)]
+struct OrderLine {
+    #[column(references = "users(id)", on_delete = "cascade")]
+    user_id: i64,
+    price: f64,
+    quantity: i64,
+    #[column(generated = "price * quantity", stored)]
+    total: f64,
+    #[column(declared_type = "VARCHAR(255)")]
+    r#type: Option<String>
+}
+
+#[allow(dead_code)]
+enum Status {
+    Active,
+    Banned
+}
+
+impl SqlType for Status {
+    const TYPE: &'static str = "TEXT";
+
+    fn check(column: &str) -> Option<Expr> {
+        Some(col(column).in_list([lit("active"), lit("banned")]))
+    }
+}
+
+#[derive(Table)]
+#[table(name = "accounts")]
+#[allow(dead_code)]
+struct Account {
+    id: i64,
+    status: Status,
+    previous: Option<Status>
+}
+
+#[test]
+fn table() {
+    assert_eq!(User::NAME, "users");
+    assert_eq!(User::table().to_string(), "CREATE TABLE IF NOT EXISTS users (\n\
+        id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,\n\
+        email TEXT NOT NULL UNIQUE CHECK(length(email) > 3) COLLATE NOCASE,\n\
+        displayName TEXT,\n\
+        active INTEGER NOT NULL CHECK(active IN (0, 1)) DEFAULT 1,\n\
+        created TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP\n\
+        ) STRICT;");
+    assert!(User::table().validate().is_ok());
+}
+
+#[test]
+fn foreign_and_generated() {
+    assert_eq!(OrderLine::NAME, "order_line");
+    assert_eq!(OrderLine::table().to_string(), "CREATE TABLE IF NOT EXISTS order_line (\n\
+        user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,\n\
+        price REAL NOT NULL,\n\
+        quantity INTEGER NOT NULL,\n\
+        total REAL NOT NULL AS (price * quantity) STORED,\n\
+        type VARCHAR(255)\n\
+        );");
+}
+
+#[test]
+fn column_constants() {
+    assert_eq!(users::TABLE, "users");
+    assert_eq!(users::display_name.name(), "displayName");
+    assert_eq!(order_line::r#type.table(), "order_line");
+    let query = select((users::id, users::email))
+        .from(users::TABLE)
+        .where_(users::active.eq(true).and(users::display_name.like("A%")))
+        .order_by(users::display_name);
+    assert_eq!(query.to_string(), "SELECT id, email FROM users WHERE active = 1 AND displayName LIKE 'A%' ORDER BY displayName ASC");
+    let query = update(users::TABLE).set_column(users::email, "a@b.c").where_(users::id.eq(1));
+    assert_eq!(query.to_string(), "UPDATE users SET email = 'a@b.c' WHERE id = 1");
+    let query = select(users::email)
+        .from(users::TABLE)
+        .inner_join(order_line::TABLE)
+        .on(users::id.qualified().eq(order_line::user_id.qualified()))
+        .where_(order_line::quantity.gt(1));
+    assert_eq!(query.to_string(), "SELECT email FROM users INNER JOIN order_line ON users.id = order_line.user_id WHERE quantity > 1");
+}
+
+#[test]
+fn user_types() {
+    assert_eq!(Account::table().to_string(), "CREATE TABLE IF NOT EXISTS accounts (\n\
+        id INTEGER NOT NULL,\n\
+        status TEXT NOT NULL CHECK(status IN ('active', 'banned')),\n\
+        previous TEXT CHECK(previous IN ('active', 'banned'))\n\
+        );");
+    let query = select(accounts::id).from(accounts::TABLE).where_(accounts::status.eq(lit("banned")).and(accounts::previous.is_null()));
+    assert_eq!(query.to_string(), "SELECT id FROM accounts WHERE status = 'banned' AND previous IS NULL");
+}